@@ -0,0 +1,35 @@
+// prepended to background shaders, which define
+// fn main_image(frag_coord: vec2<f32>) -> vec4<f32>
+// with origin at bottom left like Shadertoy
+
+struct Uniforms {
+    // size of target [px]
+    resolution: vec2<f32>,
+    // seconds since start
+    time: f32,
+    // color multiplier for night mode
+    tint: vec3<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> @builtin(position) vec4<f32> {
+    if in_vertex_index == u32(0) {
+        return vec4<f32>(-1.0, 1.0, 0.0, 1.0);
+    }
+    if in_vertex_index == u32(1) {
+        return vec4<f32>(-1.0, -3.0, 0.0, 1.0);
+    }
+    return vec4<f32>(3.0, 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let frag_coord = vec2<f32>(position.x, uniforms.resolution.y - position.y);
+    let color = main_image(frag_coord);
+    return vec4<f32>(color.rgb * uniforms.tint, 1.0);
+}
@@ -0,0 +1,60 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texcoords: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    if in_vertex_index == u32(0) {
+        out.clip_position = vec4<f32>(-1.0, 1.0, 0.0, 1.0);
+        out.texcoords = vec2<f32>(0.0, 0.0);
+    }
+    if in_vertex_index == u32(1) {
+        out.clip_position = vec4<f32>(-1.0, -3.0, 0.0, 1.0);
+        out.texcoords = vec2<f32>(0.0, 2.0);
+    }
+    if in_vertex_index == u32(2) {
+        out.clip_position = vec4<f32>(3.0, 1.0, 0.0, 1.0);
+        out.texcoords = vec2<f32>(2.0, 0.0);
+    }
+    return out;
+}
+
+struct Uniforms {
+    // distance between taps in texcoords, along one axis
+    direction: vec2<f32>,
+    radius: f32,
+    // width of fading edge of region [px]
+    feather: f32,
+    // left, top, right, bottom of blurred region [px]
+    region: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+@group(0) @binding(1)
+var tex_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // separable gaussian, run once per axis
+    let sigma = max(uniforms.radius * 0.5, 0.5);
+    let taps = i32(ceil(uniforms.radius));
+    var sum = vec4<f32>(0.0);
+    var weight_sum = 0.0;
+    for (var i = -taps; i <= taps; i++) {
+        let weight = exp(-f32(i * i) / (2.0 * sigma * sigma));
+        sum += textureSampleLevel(tex, tex_sampler, in.texcoords + uniforms.direction * f32(i), 0.0) * weight;
+        weight_sum += weight;
+    }
+
+    let p = in.clip_position.xy;
+    let outside = max(max(uniforms.region.xy - p, p - uniforms.region.zw), vec2<f32>(0.0));
+    let mask = 1.0 - smoothstep(0.0, max(uniforms.feather, 1.0), length(outside));
+    return vec4<f32>((sum / weight_sum).rgb, mask);
+}
@@ -0,0 +1,32 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texcoords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+// rect and texcoords are left, top, right, bottom in clip space and atlas space
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+    @location(0) rect: vec4<f32>,
+    @location(1) texcoords: vec4<f32>,
+    @location(2) color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let u = f32(in_vertex_index & u32(1));
+    let v = f32(in_vertex_index >> u32(1));
+    out.clip_position = vec4<f32>(mix(rect.x, rect.z, u), mix(rect.y, rect.w, v), 0.0, 1.0);
+    out.texcoords = vec2<f32>(mix(texcoords.x, texcoords.z, u), mix(texcoords.y, texcoords.w, v));
+    out.color = color;
+    return out;
+}
+
+@group(0) @binding(0)
+var atlas: texture_2d<f32>;
+@group(0) @binding(1)
+var atlas_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(atlas, atlas_sampler, in.texcoords) * in.color;
+}
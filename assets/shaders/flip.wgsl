@@ -0,0 +1,39 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texcoords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) card: vec4<f32>,
+}
+
+// rect and texcoords are left, top, right, bottom in clip space and sheet space
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+    @location(0) rect: vec4<f32>,
+    @location(1) texcoords: vec4<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) card: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let u = f32(in_vertex_index & u32(1));
+    let v = f32(in_vertex_index >> u32(1));
+    out.clip_position = vec4<f32>(mix(rect.x, rect.z, u), mix(rect.y, rect.w, v), 0.0, 1.0);
+    out.texcoords = vec2<f32>(mix(texcoords.x, texcoords.z, u), mix(texcoords.y, texcoords.w, v));
+    out.color = color;
+    out.card = card;
+    return out;
+}
+
+@group(0) @binding(0)
+var sheet: texture_2d<f32>;
+@group(0) @binding(1)
+var sheet_sampler: sampler;
+
+// half of a flap is the card with the digit printed on it
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(sheet, sheet_sampler, in.texcoords).a * in.color.a;
+    let alpha = coverage + in.card.a * (1.0 - coverage);
+    let color = (in.color.rgb * coverage + in.card.rgb * in.card.a * (1.0 - coverage)) / max(alpha, 0.0001);
+    return vec4<f32>(color, alpha);
+}
@@ -0,0 +1,33 @@
+// procedural background shown when no picture is found
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+fn noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = hash(i);
+    let b = hash(i + vec2<f32>(1.0, 0.0));
+    let c = hash(i + vec2<f32>(0.0, 1.0));
+    let d = hash(i + vec2<f32>(1.0, 1.0));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+fn main_image(frag_coord: vec2<f32>) -> vec4<f32> {
+    let uv = frag_coord / uniforms.resolution;
+    let t = uniforms.time * 0.01;
+
+    // two slowly cycling colors blended along a rotating axis, warped by noise
+    let axis = vec2<f32>(cos(t * 3.0), sin(t * 3.0));
+    let warp = noise(uv * 3.0 + vec2<f32>(t * 5.0, t * 3.0)) - 0.5;
+    let f = clamp(dot(uv - 0.5, axis) + 0.5 + warp * 0.4, 0.0, 1.0);
+    let phase = vec3<f32>(0.0, 0.33, 0.67);
+    let a = 0.5 + 0.4 * cos(6.2832 * (t + phase));
+    let b = 0.5 + 0.4 * cos(6.2832 * (t + 0.5 + phase));
+
+    // darkened for readable text, dithered against banding
+    let color = mix(a, b, f) * 0.6 + (hash(frag_coord) - 0.5) / 255.0;
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,30 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texcoords: vec2<f32>,
+}
+
+// left, top, right, bottom in clip space
+@group(0) @binding(0)
+var<uniform> rect: vec4<f32>;
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let u = f32(in_vertex_index & u32(1));
+    let v = f32(in_vertex_index >> u32(1));
+    out.clip_position = vec4<f32>(mix(rect.x, rect.z, u), mix(rect.y, rect.w, v), 0.0, 1.0);
+    out.texcoords = vec2<f32>(u, v);
+    return out;
+}
+
+@group(1) @binding(0)
+var tex: texture_2d<f32>;
+@group(1) @binding(1)
+var tex_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(tex, tex_sampler, in.texcoords);
+}
@@ -0,0 +1,65 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    // digit, alpha and height of sprite cell relative to its width
+    @location(1) params: vec3<f32>,
+}
+
+// rect is left, top, right, bottom of the tube in clip space
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+    @location(0) rect: vec4<f32>,
+    @location(1) params: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let u = f32(in_vertex_index & u32(1));
+    let v = f32(in_vertex_index >> u32(1));
+    out.clip_position = vec4<f32>(mix(rect.x, rect.z, u), mix(rect.y, rect.w, v), 0.0, 1.0);
+    out.uv = vec2<f32>(u, v);
+    out.params = params.xyz;
+    return out;
+}
+
+@group(0) @binding(0)
+var sheet: texture_2d<f32>;
+@group(0) @binding(1)
+var sheet_sampler: sampler;
+
+// brightness of the digit cathode in its cell of the sprite sheet, zero outside the cell
+fn cathode(uv: vec2<f32>, digit: f32) -> f32 {
+    let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+    let c = textureSampleLevel(sheet, sheet_sampler, vec2<f32>((digit + uv.x) / 10.0, uv.y), 0.0);
+    return select(0.0, max(c.r, max(c.g, c.b)) * c.a, inside);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let digit = in.params.x;
+    let aspect = in.params.z;
+    let core = cathode(in.uv, digit);
+
+    // neon glow spreads around the lit cathode in two rings of samples
+    var glow = 0.0;
+    for (var i = 0; i < 16; i++) {
+        let angle = f32(i) * 0.3927;
+        let radius = select(0.03, 0.07, i % 2 == 1);
+        let offset = vec2<f32>(cos(angle) * aspect, sin(angle)) * radius;
+        glow += cathode(in.uv + offset, digit);
+    }
+    glow /= 16.0;
+
+    // unlit cathodes stacked behind are faintly visible
+    var unlit = 0.0;
+    for (var d = 0; d < 10; d++) {
+        unlit = max(unlit, cathode(in.uv, f32(d)));
+    }
+
+    let hot = vec3<f32>(1.0, 0.48, 0.16);
+    let warm = vec3<f32>(1.0, 0.22, 0.02);
+    let metal = vec3<f32>(0.25, 0.2, 0.18);
+    let weight = core + glow + unlit * 0.1 + 0.0001;
+    let color = (hot * core + warm * glow + metal * unlit * 0.1) / weight;
+    let alpha = clamp(core + glow * 1.4 + unlit * 0.12, 0.0, 1.0);
+    return vec4<f32>(color, alpha * in.params.y);
+}
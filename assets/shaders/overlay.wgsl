@@ -0,0 +1,20 @@
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> @builtin(position) vec4<f32> {
+    if in_vertex_index == u32(0) {
+        return vec4<f32>(-1.0, 1.0, 0.0, 1.0);
+    }
+    if in_vertex_index == u32(1) {
+        return vec4<f32>(-1.0, -3.0, 0.0, 1.0);
+    }
+    return vec4<f32>(3.0, 1.0, 0.0, 1.0);
+}
+
+@group(0) @binding(0)
+var<uniform> color: vec4<f32>;
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return color;
+}
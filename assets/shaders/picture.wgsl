@@ -0,0 +1,67 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texcoords: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    if in_vertex_index == u32(0) {
+        out.clip_position = vec4<f32>(-1.0, 1.0, 0.0, 1.0);
+        out.texcoords = vec2<f32>(0.0, 0.0);
+    }
+    if in_vertex_index == u32(1) {
+        out.clip_position = vec4<f32>(-1.0, -3.0, 0.0, 1.0);
+        out.texcoords = vec2<f32>(0.0, 2.0);
+    }
+    if in_vertex_index == u32(2) {
+        out.clip_position = vec4<f32>(3.0, 1.0, 0.0, 1.0);
+        out.texcoords = vec2<f32>(2.0, 0.0);
+    }
+    return out;
+}
+
+struct Uniforms {
+    // scale (xy) and offset (zw) of texcoords
+    previous_transform: vec4<f32>,
+    current_transform: vec4<f32>,
+    blend: f32,
+    // white and peak levels relative to SDR white, both 1.0 on SDR surface
+    white: f32,
+    peak: f32,
+    // color multiplier for night mode
+    tint: vec3<f32>,
+    // color around pictures not covering the target
+    letterbox_color: vec4<f32>,
+    // 1.0 if pictures are repeated
+    tile: f32,
+}
+
+@group(0) @binding(0)
+var previous_tex: texture_2d<f32>;
+@group(0) @binding(1)
+var current_tex: texture_2d<f32>;
+@group(0) @binding(2)
+var tex_sampler: sampler;
+@group(0) @binding(3)
+var<uniform> uniforms: Uniforms;
+
+fn letterbox(color: vec4<f32>, texcoords: vec2<f32>) -> vec4<f32> {
+    let inside = all(texcoords >= vec2<f32>(0.0)) && all(texcoords <= vec2<f32>(1.0));
+    return select(uniforms.letterbox_color, color, inside || 0.5 < uniforms.tile);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let previous_texcoords = in.texcoords * uniforms.previous_transform.xy + uniforms.previous_transform.zw;
+    let current_texcoords = in.texcoords * uniforms.current_transform.xy + uniforms.current_transform.zw;
+    let previous = letterbox(textureSample(previous_tex, tex_sampler, previous_texcoords), previous_texcoords);
+    let current = letterbox(textureSample(current_tex, tex_sampler, current_texcoords), current_texcoords);
+    let color = mix(previous, current, uniforms.blend);
+    let rgb = color.rgb * uniforms.tint;
+    // inverse tone mapping stretches highlights toward peak, keeping shadows at white level
+    let hdr = rgb * uniforms.white + (uniforms.peak - uniforms.white) * pow(rgb, vec3<f32>(4.0));
+    return vec4<f32>(hdr, color.a);
+}
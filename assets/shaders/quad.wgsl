@@ -0,0 +1,42 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // position from the center of the shape [px]
+    @location(0) local: vec2<f32>,
+    @location(1) shape: vec4<f32>,
+    @location(2) color: vec4<f32>,
+}
+
+// rect is left, top, right, bottom in clip space including the glow margin,
+// shape is half width, half height, corner radius and glow radius [px]
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+    @location(0) rect: vec4<f32>,
+    @location(1) shape: vec4<f32>,
+    @location(2) color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let u = f32(in_vertex_index & u32(1));
+    let v = f32(in_vertex_index >> u32(1));
+    out.clip_position = vec4<f32>(mix(rect.x, rect.z, u), mix(rect.y, rect.w, v), 0.0, 1.0);
+    let extent = shape.xy + shape.w;
+    out.local = vec2<f32>(mix(-extent.x, extent.x, u), mix(-extent.y, extent.y, v));
+    out.shape = shape;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // signed distance to rounded rectangle
+    let radius = min(in.shape.z, min(in.shape.x, in.shape.y));
+    let q = abs(in.local) - in.shape.xy + radius;
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+
+    var alpha = clamp(0.5 - distance, 0.0, 1.0);
+    if in.shape.w > 0.0 {
+        let glow = exp(-max(distance, 0.0) / (in.shape.w * 0.3)) * 0.5;
+        alpha = max(alpha, glow);
+    }
+    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+}
@@ -23,13 +23,28 @@ fn vs_main(
     return out;
 }
 
+struct Uniforms {
+    // opacity at the darkest point
+    strength: f32,
+    // 0: top, 1: bottom, 2: left, 3: right, 4: vignette
+    position: f32,
+}
+
 @group(0) @binding(0)
-var tex: texture_2d<f32>;
-@group(0) @binding(1)
-var tex_sampler: sampler;
+var<uniform> uniforms: Uniforms;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(tex, tex_sampler, in.texcoords);
+    let uv = in.texcoords;
+    var t = 0.0;
+    switch i32(uniforms.position) {
+        case 0: { t = 1.0 - uv.y; }
+        case 1: { t = uv.y; }
+        case 2: { t = 1.0 - uv.x; }
+        case 3: { t = uv.x; }
+        default: { t = length(uv - 0.5) * 1.414; }
+    }
+    // darkening starts from the middle of the window
+    let alpha = smoothstep(0.4, 1.0, t) * uniforms.strength;
+    return vec4<f32>(0.0, 0.0, 0.0, alpha);
 }
-
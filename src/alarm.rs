@@ -0,0 +1,150 @@
+#[derive(Clone, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Alarm {
+    time: chrono::NaiveTime,
+    weekdays: Vec<chrono::Weekday>,
+}
+
+impl Alarm {
+    fn is_due(&self, last: chrono::NaiveDateTime, now: chrono::NaiveDateTime) -> bool {
+        [last.date(), now.date()].into_iter().any(|date| {
+            use chrono::Datelike;
+            let datetime = date.and_time(self.time);
+            let weekday_matched =
+                self.weekdays.is_empty() || self.weekdays.contains(&datetime.weekday());
+            weekday_matched && last < datetime && datetime <= now
+        })
+    }
+}
+
+impl std::str::FromStr for Alarm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use chrono::Weekday;
+
+        let (time, weekdays) = s.split_once('@').unwrap_or((s, ""));
+        let time = chrono::NaiveTime::parse_from_str(time, "%H:%M")
+            .map_err(|_| format!("invalid alarm time: {:?}", time))?;
+        let weekdays = match weekdays {
+            "" | "daily" => vec![],
+            "weekdays" => vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            "weekends" => vec![Weekday::Sat, Weekday::Sun],
+            weekdays => weekdays
+                .split(',')
+                .map(|weekday| {
+                    weekday
+                        .parse::<Weekday>()
+                        .map_err(|_| format!("invalid alarm weekday: {:?}", weekday))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Self { time, weekdays })
+    }
+}
+
+impl TryFrom<String> for Alarm {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+pub struct AlarmClock {
+    alarms: Vec<Alarm>,
+    timezone: Option<chrono_tz::Tz>,
+    duration: std::time::Duration,
+    last_update: chrono::NaiveDateTime,
+    ringing_instant: Option<std::time::Instant>,
+}
+
+impl AlarmClock {
+    pub fn new(
+        alarms: Vec<Alarm>,
+        timezone: Option<chrono_tz::Tz>,
+        duration: std::time::Duration,
+    ) -> Self {
+        let last_update = now(timezone);
+        Self {
+            alarms,
+            timezone,
+            duration,
+            last_update,
+            ringing_instant: None,
+        }
+    }
+
+    pub fn update(&mut self) -> bool {
+        let now = now(self.timezone);
+        let last = std::mem::replace(&mut self.last_update, now);
+        let triggered = self.alarms.iter().any(|alarm| alarm.is_due(last, now));
+        if triggered {
+            log::debug!("alarm triggered");
+            self.ringing_instant = Some(std::time::Instant::now());
+        }
+        triggered
+    }
+
+    /// Rings regardless of alarm times
+    pub fn trigger(&mut self) {
+        log::debug!("alarm triggered manually");
+        self.ringing_instant = Some(std::time::Instant::now());
+    }
+
+    pub fn is_ringing(&self) -> bool {
+        self.ringing_instant
+            .is_some_and(|instant| instant.elapsed() < self.duration)
+    }
+
+    pub fn dismiss(&mut self) {
+        if self.ringing_instant.take().is_some() {
+            log::debug!("alarm dismissed");
+        }
+    }
+}
+
+/// Strikes on the hour outside quiet hours
+pub struct Chime {
+    timezone: Option<chrono_tz::Tz>,
+    quiet_hours: Option<crate::night::NightSchedule>,
+    last_update: chrono::NaiveDateTime,
+}
+
+impl Chime {
+    pub fn new(
+        timezone: Option<chrono_tz::Tz>,
+        quiet_hours: Option<crate::night::NightSchedule>,
+    ) -> Self {
+        Self {
+            timezone,
+            quiet_hours,
+            last_update: now(timezone),
+        }
+    }
+
+    pub fn update(&mut self) -> bool {
+        use chrono::Timelike;
+
+        let now = now(self.timezone);
+        let last = std::mem::replace(&mut self.last_update, now);
+        let hour = now.date().and_hms_opt(now.hour(), 0, 0).unwrap();
+        let quiet = self
+            .quiet_hours
+            .is_some_and(|quiet_hours| quiet_hours.contains(now.time()));
+        last < hour && hour <= now && !quiet
+    }
+}
+
+pub fn now(timezone: Option<chrono_tz::Tz>) -> chrono::NaiveDateTime {
+    match timezone {
+        Some(timezone) => chrono::Utc::now().with_timezone(&timezone).naive_local(),
+        None => chrono::Local::now().naive_local(),
+    }
+}
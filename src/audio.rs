@@ -0,0 +1,25 @@
+/// Plays the sound file in background, volume is 1.0 at original level
+#[cfg(feature = "audio")]
+pub fn play(path: &str, volume: f32) {
+    let path = path.to_string();
+    std::thread::spawn(move || {
+        log::debug!("play {}", path);
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let (_stream, handle) = rodio::OutputStream::try_default()?;
+            let sink = rodio::Sink::try_new(&handle)?;
+            sink.set_volume(volume);
+            let file = std::io::BufReader::new(std::fs::File::open(&path)?);
+            sink.append(rodio::Decoder::new(file)?);
+            sink.sleep_until_end();
+            Ok(())
+        })();
+        if let Err(err) = result {
+            log::error!("failed to play {}: {}", path, err);
+        }
+    });
+}
+
+#[cfg(not(feature = "audio"))]
+pub fn play(path: &str, _volume: f32) {
+    log::warn!("audio feature is disabled, {} is not played", path);
+}
@@ -0,0 +1,102 @@
+pub enum Backlight {
+    /// Backlight directory under /sys/class/backlight
+    Sysfs(std::path::PathBuf),
+    /// External monitor over DDC/CI via ddcutil
+    Ddc,
+}
+
+impl Backlight {
+    pub fn new(target: &str) -> Self {
+        match target {
+            "ddc" => Self::Ddc,
+            path => Self::Sysfs(path.into()),
+        }
+    }
+
+    fn set(&self, percent: u32) -> Result<(), Box<dyn std::error::Error>> {
+        let percent = percent.min(100);
+        match self {
+            Self::Sysfs(path) => {
+                let max = std::fs::read_to_string(path.join("max_brightness"))?
+                    .trim()
+                    .parse::<u32>()?;
+                let value = max * percent / 100;
+                std::fs::write(path.join("brightness"), value.to_string())?;
+                Ok(())
+            }
+            Self::Ddc => {
+                // VCP feature 0x10 is luminance
+                let status = std::process::Command::new("ddcutil")
+                    .args(["setvcp", "10", &percent.to_string()])
+                    .status()?;
+                if !status.success() {
+                    return Err(format!("ddcutil exited with {}", status).into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Brightness levels and their sources
+pub struct Schedule {
+    pub day: u32,
+    pub night: u32,
+    pub night_mode: Option<crate::night::NightMode>,
+    /// Illuminance file of IIO light sensor and its illuminance of full brightness [lx]
+    pub ambient_light: Option<(std::path::PathBuf, f32)>,
+}
+
+impl Schedule {
+    fn percent(&self) -> Result<u32, Box<dyn std::error::Error>> {
+        if let Some((path, max_lux)) = &self.ambient_light {
+            let lux = std::fs::read_to_string(path)?.trim().parse::<f32>()?;
+            let ratio = (lux / max_lux).clamp(0.0, 1.0);
+            let percent = self.night as f32 + (self.day as f32 - self.night as f32) * ratio;
+            return Ok(percent.round() as u32);
+        }
+        let night = self
+            .night_mode
+            .as_ref()
+            .is_some_and(|night_mode| night_mode.is_active());
+        Ok(if night { self.night } else { self.day })
+    }
+}
+
+/// Follows the schedule, brightness sent through the sender overrides it until the schedule changes
+pub fn spawn(
+    backlight: Backlight,
+    schedule: Schedule,
+    interval: std::time::Duration,
+) -> std::sync::mpsc::Sender<u32> {
+    let (sender, receiver) = std::sync::mpsc::channel::<u32>();
+    std::thread::spawn(move || {
+        let mut last = None;
+        loop {
+            match schedule.percent() {
+                Ok(percent) if last != Some(percent) => {
+                    log::debug!("set backlight {}%", percent);
+                    match backlight.set(percent) {
+                        Ok(()) => last = Some(percent),
+                        Err(err) => log::error!("failed to set backlight: {}", err),
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => log::error!("failed to read ambient light: {}", err),
+            }
+            match receiver.recv_timeout(interval) {
+                Ok(percent) => {
+                    log::debug!("set backlight {}% manually", percent);
+                    if let Err(err) = backlight.set(percent) {
+                        log::error!("failed to set backlight: {}", err);
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                    std::thread::sleep(interval);
+                }
+            }
+        }
+    });
+    sender
+}
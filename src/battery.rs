@@ -0,0 +1,52 @@
+#[derive(Clone)]
+pub struct Battery {
+    pub percent: u32,
+    pub charging: bool,
+}
+
+pub fn spawn(interval: std::time::Duration) -> std::sync::mpsc::Receiver<Battery> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || loop {
+        log::debug!("query battery");
+        match query() {
+            Ok(battery) => {
+                if sender.send(battery).is_err() {
+                    break;
+                }
+            }
+            Err(err) => log::error!("failed to query battery: {}", err),
+        }
+        std::thread::sleep(interval);
+    });
+    receiver
+}
+
+/// Reads batteries under sysfs power supply class, multiple batteries are averaged
+#[cfg(target_os = "linux")]
+fn query() -> Result<Battery, Box<dyn std::error::Error>> {
+    let mut percents = vec![];
+    let mut charging = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply")? {
+        let path = entry?.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        if kind.trim() != "Battery" {
+            continue;
+        }
+        let capacity = std::fs::read_to_string(path.join("capacity"))?;
+        percents.push(capacity.trim().parse::<u32>()?);
+        let status = std::fs::read_to_string(path.join("status")).unwrap_or_default();
+        charging |= matches!(status.trim(), "Charging" | "Full");
+    }
+    if percents.is_empty() {
+        return Err("no battery found".into());
+    }
+    Ok(Battery {
+        percent: percents.iter().sum::<u32>() / percents.len() as u32,
+        charging,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn query() -> Result<Battery, Box<dyn std::error::Error>> {
+    Err("battery is not supported on this platform".into())
+}
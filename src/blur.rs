@@ -0,0 +1,340 @@
+/// Part of the background blurred
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlurRegion {
+    Whole,
+    /// Just under texts, fading out at the edges
+    Text,
+}
+
+/// Gaussian blur of the background, drawn horizontally into an intermediate texture and then
+/// vertically over the target
+pub struct BlurPipeline {
+    radius: f32,
+    target_format: wgpu::TextureFormat,
+    sample_count: u32,
+    /// Background is drawn into, and resolved into source if antialiased
+    multisampled: Option<wgpu::TextureView>,
+    source: wgpu::TextureView,
+    horizontal: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    horizontal_buffer: wgpu::Buffer,
+    vertical_buffer: wgpu::Buffer,
+    horizontal_bind_group: wgpu::BindGroup,
+    vertical_bind_group: wgpu::BindGroup,
+    horizontal_pipeline: wgpu::RenderPipeline,
+    vertical_pipeline: wgpu::RenderPipeline,
+    target_width: u32,
+    target_height: u32,
+}
+
+impl BlurPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+        target_width: u32,
+        target_height: u32,
+        radius: f32,
+    ) -> Self {
+        let create_buffer = || {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: std::mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let horizontal_buffer = create_buffer();
+        let vertical_buffer = create_buffer();
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader =
+            device.create_shader_module(wgpu::include_wgsl!("../assets/shaders/blur.wgsl"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |sample_count: u32, blend: Option<wgpu::BlendState>| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: None,
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: target_format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            })
+        };
+        // the vertical pass blends over the sharp background by the region mask
+        let horizontal_pipeline = create_pipeline(1, None);
+        let vertical_pipeline =
+            create_pipeline(sample_count, Some(wgpu::BlendState::ALPHA_BLENDING));
+
+        let source = create_view(device, target_format, target_width, target_height);
+        let horizontal = create_view(device, target_format, target_width, target_height);
+        let horizontal_bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            &source,
+            &sampler,
+            &horizontal_buffer,
+        );
+        let vertical_bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            &horizontal,
+            &sampler,
+            &vertical_buffer,
+        );
+
+        Self {
+            radius,
+            target_format,
+            sample_count,
+            multisampled: crate::gpu::multisampled_view(
+                device,
+                target_format,
+                (target_width, target_height),
+                sample_count,
+            ),
+            source,
+            horizontal,
+            sampler,
+            bind_group_layout,
+            horizontal_buffer,
+            vertical_buffer,
+            horizontal_bind_group,
+            vertical_bind_group,
+            horizontal_pipeline,
+            vertical_pipeline,
+            target_width,
+            target_height,
+        }
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, target_width: u32, target_height: u32) {
+        self.multisampled = crate::gpu::multisampled_view(
+            device,
+            self.target_format,
+            (target_width, target_height),
+            self.sample_count,
+        );
+        self.source = create_view(device, self.target_format, target_width, target_height);
+        self.horizontal = create_view(device, self.target_format, target_width, target_height);
+        self.horizontal_bind_group = create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.source,
+            &self.sampler,
+            &self.horizontal_buffer,
+        );
+        self.vertical_bind_group = create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.horizontal,
+            &self.sampler,
+            &self.vertical_buffer,
+        );
+        self.target_width = target_width;
+        self.target_height = target_height;
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// View the background is drawn into before blurring
+    pub fn target(&self) -> &wgpu::TextureView {
+        self.multisampled.as_ref().unwrap_or(&self.source)
+    }
+
+    /// Blurs the background drawn into `target` over the view, within region [px] if given
+    pub fn draw(
+        &self,
+        queue: &wgpu::Queue,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        region: Option<[f32; 4]>,
+    ) {
+        let whole = [
+            0.0,
+            0.0,
+            self.target_width as f32,
+            self.target_height as f32,
+        ];
+        let write = |buffer: &wgpu::Buffer, direction: [f32; 2], region: [f32; 4]| {
+            let uniform = [
+                direction[0],
+                direction[1],
+                self.radius,
+                self.radius,
+                region[0],
+                region[1],
+                region[2],
+                region[3],
+            ];
+            let data = uniform
+                .iter()
+                .flat_map(|c| c.to_ne_bytes())
+                .collect::<Vec<_>>();
+            queue.write_buffer(buffer, 0, &data);
+        };
+        write(
+            &self.horizontal_buffer,
+            [1.0 / self.target_width as f32, 0.0],
+            whole,
+        );
+        write(
+            &self.vertical_buffer,
+            [0.0, 1.0 / self.target_height as f32],
+            region.unwrap_or(whole),
+        );
+
+        if let Some(multisampled) = &self.multisampled {
+            crate::gpu::resolve(encoder, multisampled, &self.source);
+        }
+        for (pipeline, bind_group, view, load) in [
+            (
+                &self.horizontal_pipeline,
+                &self.horizontal_bind_group,
+                &self.horizontal,
+                wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+            ),
+            (
+                &self.vertical_pipeline,
+                &self.vertical_bind_group,
+                view,
+                wgpu::LoadOp::Load,
+            ),
+        ] {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load, store: true },
+                })],
+                depth_stencil_attachment: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+fn create_view(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> wgpu::TextureView {
+    device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
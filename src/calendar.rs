@@ -0,0 +1,111 @@
+#[derive(Clone)]
+pub struct Event {
+    pub summary: String,
+    pub start: chrono::DateTime<chrono::Utc>,
+}
+
+pub fn spawn(
+    sources: Vec<String>,
+    interval: std::time::Duration,
+) -> std::sync::mpsc::Receiver<Vec<Event>> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || loop {
+        log::debug!("fetch calendars");
+        let mut events = vec![];
+        for source in &sources {
+            match fetch(source) {
+                Ok(text) => events.extend(parse(&text)),
+                Err(err) => log::error!("failed to fetch calendar {}: {}", source, err),
+            }
+        }
+        events.sort_by_key(|event| event.start);
+        if sender.send(events).is_err() {
+            break;
+        }
+        std::thread::sleep(interval);
+    });
+    receiver
+}
+
+fn fetch(source: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Ok(ureq::get(source).call()?.into_string()?)
+    } else {
+        Ok(std::fs::read_to_string(source)?)
+    }
+}
+
+fn parse(text: &str) -> Vec<Event> {
+    // unfold continuation lines beginning with whitespace
+    let mut lines = Vec::<String>::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = vec![];
+    let mut summary = None;
+    let mut start = None;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match name {
+            "BEGIN" if value == "VEVENT" => {
+                summary = None;
+                start = None;
+            }
+            "SUMMARY" => summary = Some(unescape(value)),
+            "DTSTART" => start = parse_datetime(value, params),
+            "END" if value == "VEVENT" => {
+                if let (Some(summary), Some(start)) = (summary.take(), start.take()) {
+                    events.push(Event { summary, start });
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+fn parse_datetime(value: &str, params: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+
+    let timezone = params
+        .split(';')
+        .find_map(|param| param.strip_prefix("TZID="))
+        .and_then(|tzid| tzid.parse::<chrono_tz::Tz>().ok());
+
+    if let Some(value) = value.strip_suffix('Z') {
+        let datetime = chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+        return Some(chrono::Utc.from_utc_datetime(&datetime));
+    }
+    let datetime = chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .or_else(|_| {
+            chrono::NaiveDate::parse_from_str(value, "%Y%m%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+        })
+        .ok()?;
+    match timezone {
+        Some(timezone) => timezone
+            .from_local_datetime(&datetime)
+            .earliest()
+            .map(|datetime| datetime.with_timezone(&chrono::Utc)),
+        None => chrono::Local
+            .from_local_datetime(&datetime)
+            .earliest()
+            .map(|datetime| datetime.with_timezone(&chrono::Utc)),
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
@@ -0,0 +1,322 @@
+#[derive(Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub update_interval: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub monitor: Option<String>,
+    pub all_monitors: bool,
+    pub fullscreen: bool,
+    pub widget: bool,
+    pub transparent: bool,
+    pub layer_shell: bool,
+    pub desktop: bool,
+    pub present_mode: crate::gpu::PresentMode,
+    pub gpu_backend: Option<crate::gpu::Backend>,
+    pub adapter: Option<String>,
+    pub hdr: bool,
+    pub hdr_white: f32,
+    pub hdr_peak: f32,
+    pub msaa: u32,
+    pub picture_width: u32,
+    pub picture_height: u32,
+    pub picture_path: String,
+    pub picture_interval: u64,
+    pub picture_cache: Option<String>,
+    pub picture_cache_size: u64,
+    pub picture_transition: u64,
+    pub shader: Option<String>,
+    pub picture_order: crate::picture_store::PictureOrder,
+    pub picture_fit: crate::picture::PictureFit,
+    pub letterbox_color: crate::text::Color,
+    pub blur: f32,
+    pub blur_region: crate::blur::BlurRegion,
+    pub scrim_strength: f32,
+    pub scrim_position: crate::scrim::ScrimPosition,
+    pub caption: bool,
+    pub ken_burns_zoom: f32,
+    pub ken_burns_period: u64,
+    pub unsplash_query: Option<String>,
+    pub unsplash_access_key: Option<String>,
+    pub unsplash_cache_size: usize,
+    pub time_format: crate::text::TimeFormat,
+    pub format: Option<String>,
+    pub timezone: Option<chrono_tz::Tz>,
+    pub clocks: Vec<crate::text::Clock>,
+    pub days_until: Vec<crate::text::DaysUntil>,
+    pub show_date: bool,
+    pub date_format: String,
+    pub show_week: bool,
+    pub blink_colon: bool,
+    pub font: Option<String>,
+    pub emoji_font: Option<String>,
+    pub text_size: f32,
+    pub text_x: f32,
+    pub text_y: f32,
+    pub text_anchor: crate::text::Anchor,
+    pub face: crate::face::Face,
+    pub word_language: crate::face::WordLanguage,
+    pub nixie_sprites: Option<String>,
+    pub segment_color: Option<crate::text::Color>,
+    pub segment_off_color: Option<crate::text::Color>,
+    pub segment_glow: f32,
+    pub text_color: crate::text::Color,
+    pub text_outline: f32,
+    pub text_outline_color: crate::text::Color,
+    pub auto_contrast: bool,
+    pub text_dark_color: crate::text::Color,
+    pub palette: bool,
+    pub text_shadow: f32,
+    pub text_shadow_color: crate::text::Color,
+    pub elements: Vec<crate::text::Element>,
+    pub greeting: Option<String>,
+    pub themes: Vec<crate::theme::Theme>,
+    pub profiles: Vec<crate::profile::Profile>,
+    pub profile: Option<String>,
+    pub alarms: Vec<crate::alarm::Alarm>,
+    pub alarm_duration: u64,
+    pub alarm_sound: Option<String>,
+    pub chime: Option<String>,
+    pub quiet_hours: Option<crate::night::NightSchedule>,
+    pub volume: f32,
+    pub on_hour: Option<String>,
+    pub on_picture: Option<String>,
+    pub on_alarm: Option<String>,
+    pub eink: Option<String>,
+    pub eink_levels: u8,
+    pub eink_interval: u64,
+    pub drm: Option<String>,
+    pub screenshot_dir: String,
+    pub countdown: Option<crate::text::Countdown>,
+    pub weather_api_key: Option<String>,
+    pub weather_location: Option<String>,
+    pub weather_units: crate::weather::Units,
+    pub weather_interval: u64,
+    pub calendars: Vec<String>,
+    pub calendar_interval: u64,
+    pub ntp_server: Option<String>,
+    pub ntp_interval: u64,
+    pub ntp_threshold: f64,
+    pub stats: bool,
+    pub stats_interval: u64,
+    pub battery: bool,
+    pub battery_interval: u64,
+    pub battery_low: u32,
+    pub battery_low_color: crate::text::Color,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub sun_tint: bool,
+    pub moon: bool,
+    pub night: Option<crate::night::NightSchedule>,
+    pub night_brightness: f32,
+    pub night_red_shift: bool,
+    pub backlight: Option<String>,
+    pub backlight_day: u32,
+    pub backlight_night: u32,
+    pub backlight_interval: u64,
+    pub ambient_light: Option<String>,
+    pub ambient_light_max: f32,
+    pub burn_in_shift: f32,
+    pub burn_in_period: u64,
+    pub burn_in_picture: bool,
+    pub inhibit_sleep: bool,
+    pub keys: crate::keymap::Keymap,
+    pub http_port: Option<u16>,
+    pub mqtt_host: Option<String>,
+    pub mqtt_port: u16,
+    pub mqtt_username: Option<String>,
+    pub mqtt_password: Option<String>,
+    pub mqtt_topic: String,
+    pub dbus: bool,
+    pub control_socket: bool,
+    pub log_file: Option<String>,
+    pub log_max_size: u64,
+    pub log_max_age: u64,
+    pub log_keep: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            update_interval: 1000,
+            width: None,
+            height: None,
+            monitor: None,
+            all_monitors: false,
+            fullscreen: false,
+            widget: false,
+            transparent: false,
+            layer_shell: false,
+            desktop: false,
+            present_mode: crate::gpu::PresentMode::Fifo,
+            gpu_backend: None,
+            adapter: None,
+            hdr: false,
+            hdr_white: 203.0,
+            hdr_peak: 1000.0,
+            msaa: 1,
+            picture_width: 800,
+            picture_height: 480,
+            picture_path: "pictures".to_string(),
+            picture_interval: 3600,
+            picture_cache: None,
+            picture_cache_size: 512,
+            picture_transition: 1000,
+            shader: None,
+            picture_order: crate::picture_store::PictureOrder::Shuffle,
+            picture_fit: crate::picture::PictureFit::Cover,
+            letterbox_color: crate::text::Color([0.0, 0.0, 0.0, 1.0]),
+            blur: 0.0,
+            blur_region: crate::blur::BlurRegion::Whole,
+            scrim_strength: 0.0,
+            scrim_position: crate::scrim::ScrimPosition::Bottom,
+            caption: false,
+            ken_burns_zoom: 0.0,
+            ken_burns_period: 120,
+            unsplash_query: None,
+            unsplash_access_key: None,
+            unsplash_cache_size: 10,
+            time_format: crate::text::TimeFormat::H24,
+            format: None,
+            timezone: None,
+            clocks: Vec::new(),
+            days_until: Vec::new(),
+            show_date: true,
+            date_format: "%a %d %b %Y".to_string(),
+            show_week: false,
+            blink_colon: false,
+            font: None,
+            emoji_font: None,
+            text_size: 128.0,
+            text_x: 0.5,
+            text_y: 0.5,
+            text_anchor: crate::text::Anchor::Center,
+            face: crate::face::Face::Text,
+            word_language: crate::face::WordLanguage::En,
+            nixie_sprites: None,
+            segment_color: None,
+            segment_off_color: None,
+            segment_glow: 12.0,
+            text_color: crate::text::Color([1.0, 1.0, 1.0, 1.0]),
+            text_outline: 0.0,
+            text_outline_color: crate::text::Color([0.0, 0.0, 0.0, 1.0]),
+            auto_contrast: false,
+            text_dark_color: crate::text::Color([0.0, 0.0, 0.0, 1.0]),
+            palette: false,
+            text_shadow: 0.0,
+            text_shadow_color: crate::text::Color([0.0, 0.0, 0.0, 0.5]),
+            elements: vec![],
+            greeting: None,
+            themes: vec![],
+            profiles: vec![],
+            profile: None,
+            alarms: Vec::new(),
+            alarm_duration: 60,
+            alarm_sound: None,
+            chime: None,
+            quiet_hours: None,
+            volume: 1.0,
+            on_hour: None,
+            on_picture: None,
+            on_alarm: None,
+            eink: None,
+            eink_levels: 2,
+            eink_interval: 60,
+            drm: None,
+            screenshot_dir: ".".to_string(),
+            countdown: None,
+            weather_api_key: None,
+            weather_location: None,
+            weather_units: crate::weather::Units::Metric,
+            weather_interval: 600,
+            calendars: Vec::new(),
+            calendar_interval: 900,
+            ntp_server: None,
+            ntp_interval: 3600,
+            ntp_threshold: 1.0,
+            stats: false,
+            stats_interval: 5,
+            battery: false,
+            battery_interval: 60,
+            battery_low: 20,
+            battery_low_color: crate::text::Color([1.0, 0.25, 0.25, 1.0]),
+            latitude: None,
+            longitude: None,
+            sun_tint: false,
+            moon: false,
+            night: None,
+            night_brightness: 0.3,
+            night_red_shift: false,
+            backlight: None,
+            backlight_day: 100,
+            backlight_night: 20,
+            backlight_interval: 60,
+            ambient_light: None,
+            ambient_light_max: 500.0,
+            burn_in_shift: 0.0,
+            burn_in_period: 300,
+            burn_in_picture: false,
+            inhibit_sleep: false,
+            keys: Default::default(),
+            http_port: None,
+            mqtt_host: None,
+            mqtt_port: 1883,
+            mqtt_username: None,
+            mqtt_password: None,
+            mqtt_topic: "clock-app".to_string(),
+            dbus: false,
+            control_socket: false,
+            log_file: None,
+            log_max_size: 1024,
+            log_max_age: 24,
+            log_keep: 5,
+        }
+    }
+}
+
+impl Config {
+    /// Window size, small by default for widget
+    pub fn size(&self) -> (u32, u32) {
+        let (width, height) = if self.widget { (360, 120) } else { (800, 480) };
+        (self.width.unwrap_or(width), self.height.unwrap_or(height))
+    }
+
+    pub fn load(path: &str) -> Result<Self, crate::error::Error> {
+        log::debug!("load config");
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| crate::error::Error::ConfigRead(path.to_string(), err))?;
+        toml::from_str(&text).map_err(|err| crate::error::Error::ConfigParse(path.to_string(), err))
+    }
+}
+
+/// Watches the configuration file, sending on every change of it
+pub fn watch(
+    path: &str,
+) -> notify::Result<(notify::RecommendedWatcher, std::sync::mpsc::Receiver<()>)> {
+    use notify::Watcher;
+
+    let path = std::path::Path::new(path);
+    let name = path.file_name().map(|name| name.to_os_string());
+    // editors replace the file on save, so its directory is watched
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+            Ok(event)
+                if !event.kind.is_access()
+                    && event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == name.as_deref()) =>
+            {
+                let _ = sender.send(());
+            }
+            Ok(_) => {}
+            Err(err) => log::error!("failed to watch config: {}", err),
+        })?;
+    watcher.watch(dir, notify::RecursiveMode::NonRecursive)?;
+    Ok((watcher, receiver))
+}
@@ -0,0 +1,27 @@
+/// Command from remote control of a running instance
+pub enum Command {
+    NextPicture,
+    PreviousPicture,
+    /// Message shown on screen, `None` clears it
+    SetMessage(Option<String>),
+    TriggerAlarm,
+    DismissAlarm,
+    /// Display brightness [%]
+    SetBrightness(u32),
+    /// Pauses or resumes picture rotation
+    SetPaused(bool),
+    /// Profile chosen by name, `None` returns to the scheduled one
+    SetProfile(Option<String>),
+}
+
+/// State reported to remote control
+#[derive(Clone, Default, PartialEq, serde::Serialize)]
+pub struct Status {
+    /// Current picture of each window
+    pub pictures: Vec<String>,
+    pub message: Option<String>,
+    pub alarm_ringing: bool,
+    pub paused: bool,
+    /// Name of the profile in use
+    pub profile: Option<String>,
+}
@@ -0,0 +1,84 @@
+/// Serves `org.gossiperloturot.Clock` on session bus, commands are handed over through the receiver
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn spawn(
+    status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+) -> std::sync::mpsc::Receiver<crate::control::Command> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        log::debug!("start d-bus service");
+        let service = Service { sender, status };
+        let connection = zbus::blocking::ConnectionBuilder::session()
+            .and_then(|builder| builder.name("org.gossiperloturot.Clock"))
+            .and_then(|builder| builder.serve_at("/org/gossiperloturot/Clock", service))
+            .and_then(|builder| builder.build());
+        match connection {
+            // requests are served by the executor of the connection while it is alive
+            Ok(_connection) => loop {
+                std::thread::park();
+            },
+            Err(err) => log::error!("failed to start d-bus service: {}", err),
+        }
+    });
+    receiver
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn spawn(
+    _status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+) -> std::sync::mpsc::Receiver<crate::control::Command> {
+    log::error!("d-bus is not supported on this platform");
+    std::sync::mpsc::channel().1
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+struct Service {
+    sender: std::sync::mpsc::Sender<crate::control::Command>,
+    status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[zbus::dbus_interface(name = "org.gossiperloturot.Clock")]
+impl Service {
+    fn next_picture(&self) {
+        let _ = self.sender.send(crate::control::Command::NextPicture);
+    }
+
+    fn previous_picture(&self) {
+        let _ = self.sender.send(crate::control::Command::PreviousPicture);
+    }
+
+    /// Empty message clears it
+    fn set_message(&self, message: String) {
+        let message = (!message.is_empty()).then_some(message);
+        let _ = self
+            .sender
+            .send(crate::control::Command::SetMessage(message));
+    }
+
+    /// Empty name returns to the scheduled profile
+    fn set_profile(&self, profile: String) {
+        let profile = (!profile.is_empty()).then_some(profile);
+        let _ = self
+            .sender
+            .send(crate::control::Command::SetProfile(profile));
+    }
+
+    fn set_brightness(&self, percent: u32) {
+        let _ = self
+            .sender
+            .send(crate::control::Command::SetBrightness(percent));
+    }
+
+    fn trigger_alarm(&self) {
+        let _ = self.sender.send(crate::control::Command::TriggerAlarm);
+    }
+
+    fn dismiss_alarm(&self) {
+        let _ = self.sender.send(crate::control::Command::DismissAlarm);
+    }
+
+    /// Status as JSON
+    fn status(&self) -> String {
+        serde_json::to_string(&*self.status.lock().unwrap()).unwrap()
+    }
+}
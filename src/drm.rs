@@ -0,0 +1,475 @@
+/// Scanout buffers shown by DRM/KMS on the first connected monitor, without a display server
+#[cfg(target_os = "linux")]
+pub struct Output {
+    file: std::fs::File,
+    /// Frames are written into the buffer not scanned out, then flipped to
+    buffers: [Buffer; 2],
+    front: usize,
+    /// Flip requested and not completed yet, while the back buffer may still be scanned out
+    flip_pending: bool,
+    size: (u32, u32),
+    connector_id: u32,
+    crtc_id: u32,
+    mode: ffi::ModeInfo,
+    /// Mode set before, restored on drop
+    previous_crtc: ffi::ModeCrtc,
+}
+
+/// Dumb buffer mapped into memory, with its framebuffer
+#[cfg(target_os = "linux")]
+struct Buffer {
+    fb_id: u32,
+    map: *mut u8,
+    map_len: usize,
+    pitch: u32,
+}
+
+#[cfg(target_os = "linux")]
+impl Buffer {
+    fn new(fd: std::os::fd::RawFd, (width, height): (u32, u32)) -> std::io::Result<Self> {
+        let mut dumb = ffi::ModeCreateDumb {
+            width,
+            height,
+            bpp: 32,
+            ..Default::default()
+        };
+        ffi::ioctl(fd, ffi::CREATE_DUMB, &mut dumb)?;
+        let mut framebuffer = ffi::ModeFbCmd {
+            width,
+            height,
+            pitch: dumb.pitch,
+            bpp: 32,
+            depth: 24,
+            handle: dumb.handle,
+            ..Default::default()
+        };
+        ffi::ioctl(fd, ffi::ADD_FB, &mut framebuffer)?;
+        let mut map_dumb = ffi::ModeMapDumb {
+            handle: dumb.handle,
+            ..Default::default()
+        };
+        ffi::ioctl(fd, ffi::MAP_DUMB, &mut map_dumb)?;
+        let map_len = dumb.size as usize;
+        let map = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                map_dumb.offset as libc::off_t,
+            )
+        };
+        if map == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self {
+            fb_id: framebuffer.fb_id,
+            map: map as *mut u8,
+            map_len,
+            pitch: dumb.pitch,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.map as *mut libc::c_void, self.map_len) };
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Output {
+    pub fn open(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+        let fd = file.as_raw_fd();
+
+        let mut resources = ffi::ModeCardRes::default();
+        ffi::ioctl(fd, ffi::GET_RESOURCES, &mut resources)?;
+        let mut crtcs = vec![0_u32; resources.count_crtcs as usize];
+        let mut connectors = vec![0_u32; resources.count_connectors as usize];
+        let mut resources = ffi::ModeCardRes {
+            crtc_id_ptr: crtcs.as_mut_ptr() as u64,
+            connector_id_ptr: connectors.as_mut_ptr() as u64,
+            count_crtcs: resources.count_crtcs,
+            count_connectors: resources.count_connectors,
+            ..Default::default()
+        };
+        ffi::ioctl(fd, ffi::GET_RESOURCES, &mut resources)?;
+
+        let (connector, mode, encoders) = connectors
+            .iter()
+            .find_map(|&connector_id| {
+                let mut connector = ffi::ModeGetConnector {
+                    connector_id,
+                    ..Default::default()
+                };
+                ffi::ioctl(fd, ffi::GET_CONNECTOR, &mut connector).ok()?;
+                if connector.connection != ffi::CONNECTED || connector.count_modes == 0 {
+                    return None;
+                }
+                let mut modes = vec![ffi::ModeInfo::default(); connector.count_modes as usize];
+                let mut encoders = vec![0_u32; connector.count_encoders as usize];
+                let mut connector = ffi::ModeGetConnector {
+                    connector_id,
+                    modes_ptr: modes.as_mut_ptr() as u64,
+                    count_modes: connector.count_modes,
+                    encoders_ptr: encoders.as_mut_ptr() as u64,
+                    count_encoders: connector.count_encoders,
+                    ..Default::default()
+                };
+                ffi::ioctl(fd, ffi::GET_CONNECTOR, &mut connector).ok()?;
+                // preferred mode is listed first
+                Some((connector, modes[0], encoders))
+            })
+            .ok_or("no connected monitor")?;
+
+        // crtc driving the monitor now, or any one its encoders can drive
+        let crtc_id = std::iter::once(connector.encoder_id)
+            .chain(encoders)
+            .filter(|&encoder_id| encoder_id != 0)
+            .find_map(|encoder_id| {
+                let mut encoder = ffi::ModeGetEncoder {
+                    encoder_id,
+                    ..Default::default()
+                };
+                ffi::ioctl(fd, ffi::GET_ENCODER, &mut encoder).ok()?;
+                if encoder_id == connector.encoder_id && encoder.crtc_id != 0 {
+                    return Some(encoder.crtc_id);
+                }
+                crtcs
+                    .iter()
+                    .enumerate()
+                    .find(|(index, _)| encoder.possible_crtcs & (1 << index) != 0)
+                    .map(|(_, &crtc_id)| crtc_id)
+            })
+            .ok_or("no crtc for the monitor")?;
+
+        let size = (mode.hdisplay as u32, mode.vdisplay as u32);
+        let buffers = [Buffer::new(fd, size)?, Buffer::new(fd, size)?];
+
+        let mut previous_crtc = ffi::ModeCrtc {
+            crtc_id,
+            ..Default::default()
+        };
+        ffi::ioctl(fd, ffi::GET_CRTC, &mut previous_crtc)?;
+        let mut connector_id = connector.connector_id;
+        let mut crtc = ffi::ModeCrtc {
+            set_connectors_ptr: &mut connector_id as *mut u32 as u64,
+            count_connectors: 1,
+            crtc_id,
+            fb_id: buffers[0].fb_id,
+            mode_valid: 1,
+            mode,
+            ..Default::default()
+        };
+        ffi::ioctl(fd, ffi::SET_CRTC, &mut crtc)?;
+        log::debug!(
+            "set mode {}x{}@{} on connector {}",
+            size.0,
+            size.1,
+            mode.vrefresh,
+            connector.connector_id
+        );
+
+        Ok(Self {
+            file,
+            buffers,
+            front: 0,
+            flip_pending: false,
+            size,
+            connector_id,
+            crtc_id,
+            mode,
+            previous_crtc,
+        })
+    }
+
+    /// Resolution of the mode set
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Writes frame into the back buffer as XRGB8888 and flips to it, so that a half written
+    /// frame is never scanned out
+    pub fn show(&mut self, image: &image::RgbaImage) {
+        use std::os::fd::AsRawFd;
+
+        if self.flip_pending {
+            self.wait_flip();
+        }
+        let back = &self.buffers[1 - self.front];
+        let buffer = unsafe { std::slice::from_raw_parts_mut(back.map, back.map_len) };
+        let width = image.width().min(self.size.0) as usize;
+        for (y, row) in image.rows().take(self.size.1 as usize).enumerate() {
+            let offset = y * back.pitch as usize;
+            for (pixel, out) in row
+                .take(width)
+                .zip(buffer[offset..offset + 4 * width].chunks_exact_mut(4))
+            {
+                let [r, g, b, _] = pixel.0;
+                out.copy_from_slice(&[b, g, r, 0xff]);
+            }
+        }
+
+        let fd = self.file.as_raw_fd();
+        let mut page_flip = ffi::ModeCrtcPageFlip {
+            crtc_id: self.crtc_id,
+            fb_id: back.fb_id,
+            flags: ffi::PAGE_FLIP_EVENT,
+            ..Default::default()
+        };
+        // drivers without page flip set the crtc instead
+        if let Err(err) = ffi::ioctl(fd, ffi::PAGE_FLIP, &mut page_flip) {
+            log::debug!("failed to flip page: {}, set crtc instead", err);
+            let mut crtc = ffi::ModeCrtc {
+                set_connectors_ptr: &mut self.connector_id as *mut u32 as u64,
+                count_connectors: 1,
+                crtc_id: self.crtc_id,
+                fb_id: back.fb_id,
+                mode_valid: 1,
+                mode: self.mode,
+                ..Default::default()
+            };
+            if let Err(err) = ffi::ioctl(fd, ffi::SET_CRTC, &mut crtc) {
+                log::error!("failed to show frame: {}", err);
+                return;
+            }
+        } else {
+            self.flip_pending = true;
+        }
+        self.front = 1 - self.front;
+    }
+
+    /// Blocks until the event of the pending flip is read, after which the buffer flipped from
+    /// is no longer scanned out
+    fn wait_flip(&mut self) {
+        use std::io::Read;
+
+        let mut events = [0; 1024];
+        while self.flip_pending {
+            let len = match (&self.file).read(&mut events) {
+                Ok(len) => len,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    log::error!("failed to read flip event: {}", err);
+                    break;
+                }
+            };
+            // events are a `drm_event` header of type and length, followed by their body
+            let mut offset = 0;
+            while offset + 8 <= len {
+                let kind = u32::from_ne_bytes(events[offset..offset + 4].try_into().unwrap());
+                let length = u32::from_ne_bytes(events[offset + 4..offset + 8].try_into().unwrap());
+                if kind == ffi::EVENT_FLIP_COMPLETE {
+                    self.flip_pending = false;
+                }
+                offset += (length as usize).max(8);
+            }
+        }
+        self.flip_pending = false;
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Output {
+    fn drop(&mut self) {
+        use std::os::fd::AsRawFd;
+
+        if self.flip_pending {
+            self.wait_flip();
+        }
+        if self.previous_crtc.mode_valid != 0 && self.previous_crtc.fb_id != 0 {
+            let mut crtc = ffi::ModeCrtc {
+                set_connectors_ptr: &mut self.connector_id as *mut u32 as u64,
+                count_connectors: 1,
+                ..self.previous_crtc
+            };
+            let _ = ffi::ioctl(self.file.as_raw_fd(), ffi::SET_CRTC, &mut crtc);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct Output;
+
+#[cfg(not(target_os = "linux"))]
+impl Output {
+    pub fn open(_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Err("DRM/KMS is not supported on this platform".into())
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (0, 0)
+    }
+
+    pub fn show(&mut self, _image: &image::RgbaImage) {}
+}
+
+/// Structures and requests of `drm_mode.h`
+#[cfg(target_os = "linux")]
+mod ffi {
+    pub const CONNECTED: u32 = 1;
+
+    pub const GET_RESOURCES: u64 = iowr::<ModeCardRes>(0xa0);
+    pub const GET_CRTC: u64 = iowr::<ModeCrtc>(0xa1);
+    pub const SET_CRTC: u64 = iowr::<ModeCrtc>(0xa2);
+    pub const GET_ENCODER: u64 = iowr::<ModeGetEncoder>(0xa6);
+    pub const GET_CONNECTOR: u64 = iowr::<ModeGetConnector>(0xa7);
+    pub const ADD_FB: u64 = iowr::<ModeFbCmd>(0xae);
+    pub const PAGE_FLIP: u64 = iowr::<ModeCrtcPageFlip>(0xb0);
+    pub const CREATE_DUMB: u64 = iowr::<ModeCreateDumb>(0xb2);
+    pub const MAP_DUMB: u64 = iowr::<ModeMapDumb>(0xb3);
+
+    /// Flag of page flip asking for an event on completion
+    pub const PAGE_FLIP_EVENT: u32 = 0x01;
+    /// Type of `drm_event` read when a page flip completed
+    pub const EVENT_FLIP_COMPLETE: u32 = 0x02;
+
+    /// `_IOWR('d', nr, T)` of the generic ioctl encoding used by x86 and ARM
+    const fn iowr<T>(nr: u64) -> u64 {
+        (3 << 30) | ((std::mem::size_of::<T>() as u64) << 16) | ((b'd' as u64) << 8) | nr
+    }
+
+    pub fn ioctl<T>(fd: std::os::fd::RawFd, request: u64, arg: &mut T) -> std::io::Result<()> {
+        loop {
+            if unsafe { libc::ioctl(fd, request as _, arg as *mut T) } == 0 {
+                return Ok(());
+            }
+            let err = std::io::Error::last_os_error();
+            if !matches!(err.raw_os_error(), Some(libc::EINTR | libc::EAGAIN)) {
+                return Err(err);
+            }
+        }
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeCardRes {
+        pub fb_id_ptr: u64,
+        pub crtc_id_ptr: u64,
+        pub connector_id_ptr: u64,
+        pub encoder_id_ptr: u64,
+        pub count_fbs: u32,
+        pub count_crtcs: u32,
+        pub count_connectors: u32,
+        pub count_encoders: u32,
+        pub min_width: u32,
+        pub max_width: u32,
+        pub min_height: u32,
+        pub max_height: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    pub struct ModeInfo {
+        pub clock: u32,
+        pub hdisplay: u16,
+        pub hsync_start: u16,
+        pub hsync_end: u16,
+        pub htotal: u16,
+        pub hskew: u16,
+        pub vdisplay: u16,
+        pub vsync_start: u16,
+        pub vsync_end: u16,
+        pub vtotal: u16,
+        pub vscan: u16,
+        pub vrefresh: u32,
+        pub flags: u32,
+        pub kind: u32,
+        pub name: [u8; 32],
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    pub struct ModeCrtc {
+        pub set_connectors_ptr: u64,
+        pub count_connectors: u32,
+        pub crtc_id: u32,
+        pub fb_id: u32,
+        pub x: u32,
+        pub y: u32,
+        pub gamma_size: u32,
+        pub mode_valid: u32,
+        pub mode: ModeInfo,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeCrtcPageFlip {
+        pub crtc_id: u32,
+        pub fb_id: u32,
+        pub flags: u32,
+        pub reserved: u32,
+        pub user_data: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeGetEncoder {
+        pub encoder_id: u32,
+        pub encoder_type: u32,
+        pub crtc_id: u32,
+        pub possible_crtcs: u32,
+        pub possible_clones: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeGetConnector {
+        pub encoders_ptr: u64,
+        pub modes_ptr: u64,
+        pub props_ptr: u64,
+        pub prop_values_ptr: u64,
+        pub count_modes: u32,
+        pub count_props: u32,
+        pub count_encoders: u32,
+        pub encoder_id: u32,
+        pub connector_id: u32,
+        pub connector_type: u32,
+        pub connector_type_id: u32,
+        pub connection: u32,
+        pub mm_width: u32,
+        pub mm_height: u32,
+        pub subpixel: u32,
+        pub pad: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeFbCmd {
+        pub fb_id: u32,
+        pub width: u32,
+        pub height: u32,
+        pub pitch: u32,
+        pub bpp: u32,
+        pub depth: u32,
+        pub handle: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeCreateDumb {
+        pub height: u32,
+        pub width: u32,
+        pub bpp: u32,
+        pub flags: u32,
+        pub handle: u32,
+        pub pitch: u32,
+        pub size: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ModeMapDumb {
+        pub handle: u32,
+        pub pad: u32,
+        pub offset: u64,
+    }
+}
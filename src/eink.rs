@@ -0,0 +1,151 @@
+/// E-paper display fed with dithered gray frames, only changed rows are written so that
+/// panels supporting partial refresh update just the region of the change
+pub struct Display {
+    output: Output,
+    levels: u8,
+    previous: Option<image::GrayImage>,
+}
+
+enum Output {
+    /// Linux framebuffer device (e.g. /dev/fb0), which SPI e-ink drivers also expose
+    Framebuffer {
+        file: std::fs::File,
+        bits_per_pixel: u32,
+        stride: u32,
+        size: (u32, u32),
+    },
+    /// PNG file read by an external driver
+    Image(String),
+}
+
+impl Display {
+    pub fn open(path: &str, levels: u8) -> Result<Self, Box<dyn std::error::Error>> {
+        let output = match path.strip_prefix("/dev/") {
+            Some(name) if name.starts_with("fb") => {
+                let read = |attribute: &str| {
+                    std::fs::read_to_string(format!("/sys/class/graphics/{}/{}", name, attribute))
+                };
+                let bits_per_pixel = read("bits_per_pixel")?.trim().parse()?;
+                let stride = read("stride")?.trim().parse()?;
+                let size = read("virtual_size")?;
+                let (width, height) = size
+                    .trim()
+                    .split_once(',')
+                    .ok_or_else(|| format!("invalid framebuffer size: {:?}", size))?;
+                if ![8, 16, 32].contains(&bits_per_pixel) {
+                    return Err(format!("unsupported framebuffer depth: {}", bits_per_pixel).into());
+                }
+                Output::Framebuffer {
+                    file: std::fs::OpenOptions::new().write(true).open(path)?,
+                    bits_per_pixel,
+                    stride,
+                    size: (width.parse()?, height.parse()?),
+                }
+            }
+            _ => Output::Image(path.to_string()),
+        };
+        Ok(Self {
+            output,
+            levels: levels.max(2),
+            previous: None,
+        })
+    }
+
+    /// Writes frame if it differs from the previous one
+    pub fn show(&mut self, image: &image::RgbaImage) -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::{Seek, Write};
+
+        let frame = dither(image, self.levels);
+        let rows = match &self.previous {
+            Some(previous) if previous.dimensions() == frame.dimensions() => {
+                let width = frame.width() as usize;
+                let changed = frame
+                    .chunks(width)
+                    .zip(previous.chunks(width))
+                    .enumerate()
+                    .filter(|(_, (a, b))| a != b)
+                    .map(|(y, _)| y as u32)
+                    .collect::<Vec<_>>();
+                match (changed.first(), changed.last()) {
+                    (Some(first), Some(last)) => *first..*last + 1,
+                    _ => return Ok(()),
+                }
+            }
+            _ => 0..frame.height(),
+        };
+        log::debug!("write e-ink rows {:?}", rows);
+
+        match &mut self.output {
+            Output::Framebuffer {
+                file,
+                bits_per_pixel,
+                stride,
+                size: (width, height),
+            } => {
+                let width = frame.width().min(*width);
+                for y in rows.start..rows.end.min(*height) {
+                    let row = (0..width)
+                        .flat_map(|x| {
+                            let value = frame.get_pixel(x, y).0[0];
+                            match bits_per_pixel {
+                                8 => vec![value],
+                                16 => {
+                                    let rgb565 = ((value as u16 >> 3) << 11)
+                                        | ((value as u16 >> 2) << 5)
+                                        | (value as u16 >> 3);
+                                    rgb565.to_ne_bytes().to_vec()
+                                }
+                                _ => vec![value, value, value, 0xff],
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    file.seek(std::io::SeekFrom::Start((y * *stride) as u64))?;
+                    file.write_all(&row)?;
+                }
+                file.flush()?;
+            }
+            Output::Image(path) => {
+                frame.save_with_format(path, image::ImageFormat::Png)?;
+            }
+        }
+
+        self.previous = Some(frame);
+        Ok(())
+    }
+}
+
+/// Reduces image to gray levels with Floyd-Steinberg error diffusion
+fn dither(image: &image::RgbaImage, levels: u8) -> image::GrayImage {
+    let (width, height) = image.dimensions();
+    let mut values = image
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b, _] = pixel.0.map(|channel| channel as f32 / 255.0);
+            0.2126 * r + 0.7152 * g + 0.0722 * b
+        })
+        .collect::<Vec<_>>();
+
+    let steps = (levels - 1) as f32;
+    let mut frame = image::GrayImage::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let i = (y * width + x) as usize;
+            let value = values[i].clamp(0.0, 1.0);
+            let quantized = (value * steps).round() / steps;
+            frame.put_pixel(x, y, image::Luma([(quantized * 255.0) as u8]));
+
+            let error = value - quantized;
+            let mut spread = |dx: i32, dy: u32, weight: f32| {
+                let (x, y) = (x as i32 + dx, y + dy);
+                if 0 <= x && x < width as i32 && y < height {
+                    values[(y * width + x as u32) as usize] += error * weight;
+                }
+            };
+            spread(1, 0, 7.0 / 16.0);
+            spread(-1, 1, 3.0 / 16.0);
+            spread(0, 1, 5.0 / 16.0);
+            spread(1, 1, 1.0 / 16.0);
+        }
+    }
+    frame
+}
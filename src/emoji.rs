@@ -0,0 +1,333 @@
+/// Side of the texture caching emoji images [px]
+const ATLAS_SIZE: u32 = 1024;
+
+/// Floats of an instance: rect, texcoords and color
+const INSTANCE_LEN: usize = 12;
+
+/// Color glyphs stored as bitmap images (e.g. Noto Color Emoji), which glyph brush cannot draw
+pub struct EmojiPipeline {
+    font: wgpu_glyph::ab_glyph::FontArc,
+    atlas: wgpu::Texture,
+    atlas_bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+    /// Placed images by glyph and strike size, `None` if the glyph has no image
+    cache: std::collections::HashMap<(wgpu_glyph::ab_glyph::GlyphId, u16), Option<Entry>>,
+    /// Shelf packing cursor of atlas: x, y and height of current row
+    cursor: (u32, u32, u32),
+    instances: Vec<[f32; INSTANCE_LEN]>,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    texcoords: [f32; 4],
+    /// Offset of bottom left corner from origin and size, at image scale [px]
+    rect: [f32; 4],
+    /// Pixels per em of image
+    scale: f32,
+}
+
+impl EmojiPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+        font: wgpu_glyph::ab_glyph::FontArc,
+    ) -> Self {
+        let atlas = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: ATLAS_SIZE,
+                height: ATLAS_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let atlas_view = atlas.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let atlas_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+        let atlas_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &atlas_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let shader =
+            device.create_shader_module(wgpu::include_wgsl!("../assets/shaders/emoji.wgsl"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&atlas_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; INSTANCE_LEN]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x4,
+                        1 => Float32x4,
+                        2 => Float32x4,
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        Self {
+            font,
+            atlas,
+            atlas_bind_group,
+            pipeline,
+            cache: std::collections::HashMap::new(),
+            cursor: (0, 0, 0),
+            instances: vec![],
+        }
+    }
+
+    /// Whether the glyph of character is taken from emoji font
+    pub fn contains(&self, c: char) -> bool {
+        use wgpu_glyph::ab_glyph::Font;
+
+        self.font.glyph_id(c).0 != 0
+    }
+
+    /// Queues image of glyph laid out by glyph brush in emoji font
+    pub fn queue(
+        &mut self,
+        queue: &wgpu::Queue,
+        glyph: &wgpu_glyph::ab_glyph::Glyph,
+        color: [f32; 4],
+        (target_width, target_height): (u32, u32),
+    ) {
+        use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+        let font_scale = self.font.as_scaled(glyph.scale).h_scale_factor()
+            * self.font.units_per_em().unwrap_or(1000.0);
+        let size = font_scale.round().clamp(1.0, u16::MAX as f32) as u16;
+        let Some(entry) = self.entry(queue, glyph.id, size) else {
+            return;
+        };
+
+        let ratio = font_scale / entry.scale;
+        let left = glyph.position.x + entry.rect[0] * ratio;
+        let bottom = glyph.position.y - entry.rect[1] * ratio;
+        let right = left + entry.rect[2] * ratio;
+        let top = bottom - entry.rect[3] * ratio;
+        let to_clip = |x: f32, y: f32| {
+            (
+                x / target_width as f32 * 2.0 - 1.0,
+                1.0 - y / target_height as f32 * 2.0,
+            )
+        };
+        let (left, top) = to_clip(left, top);
+        let (right, bottom) = to_clip(right, bottom);
+        let [u0, v0, u1, v1] = entry.texcoords;
+        let [r, g, b, a] = color;
+        self.instances
+            .push([left, top, right, bottom, u0, v0, u1, v1, r, g, b, a]);
+    }
+
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        use wgpu::util::DeviceExt;
+
+        let instances = std::mem::take(&mut self.instances);
+        if instances.is_empty() {
+            return;
+        }
+
+        let data = instances
+            .iter()
+            .flatten()
+            .flat_map(|c| c.to_ne_bytes())
+            .collect::<Vec<_>>();
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &data,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.atlas_bind_group, &[]);
+        pass.set_vertex_buffer(0, instance_buffer.slice(..));
+        pass.draw(0..4, 0..instances.len() as u32);
+    }
+
+    /// Looks up the image of glyph, placing it into atlas on the first use
+    fn entry(
+        &mut self,
+        queue: &wgpu::Queue,
+        id: wgpu_glyph::ab_glyph::GlyphId,
+        size: u16,
+    ) -> Option<Entry> {
+        use wgpu_glyph::ab_glyph::Font;
+
+        if let Some(entry) = self.cache.get(&(id, size)) {
+            return *entry;
+        }
+
+        let font = self.font.clone();
+        let raster = font.glyph_raster_image(id, size);
+        let image = raster.as_ref().and_then(|raster| match raster.format {
+            wgpu_glyph::ab_glyph::GlyphImageFormat::Png => {
+                match image::load_from_memory_with_format(raster.data, image::ImageFormat::Png) {
+                    Ok(image) => Some(image.to_rgba8()),
+                    Err(err) => {
+                        log::error!("failed to decode emoji image: {}", err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        });
+        let entry = raster
+            .zip(image)
+            .and_then(|(raster, image)| self.place(queue, &image, raster.origin, raster.scale));
+        self.cache.insert((id, size), entry);
+        entry
+    }
+
+    fn place(
+        &mut self,
+        queue: &wgpu::Queue,
+        image: &image::RgbaImage,
+        origin: wgpu_glyph::ab_glyph::Point,
+        scale: f32,
+    ) -> Option<Entry> {
+        let (width, height) = image.dimensions();
+        if ATLAS_SIZE < width || ATLAS_SIZE < height {
+            log::warn!("emoji image is too large: {}x{}", width, height);
+            return None;
+        }
+
+        let (mut x, mut y, mut row_height) = self.cursor;
+        if ATLAS_SIZE < x + width {
+            (x, y, row_height) = (0, y + row_height, 0);
+        }
+        if ATLAS_SIZE < y + height {
+            // previous placements are dropped when the atlas is full
+            log::debug!("clear emoji atlas");
+            self.cache.clear();
+            (x, y, row_height) = (0, 0, 0);
+        }
+        self.cursor = (x + width, y, row_height.max(height));
+
+        let texture_size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.atlas,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            texture_size,
+        );
+
+        let atlas_size = ATLAS_SIZE as f32;
+        Some(Entry {
+            texcoords: [
+                x as f32 / atlas_size,
+                y as f32 / atlas_size,
+                (x + width) as f32 / atlas_size,
+                (y + height) as f32 / atlas_size,
+            ],
+            rect: [origin.x, origin.y, width as f32, height as f32],
+            scale,
+        })
+    }
+}
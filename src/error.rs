@@ -0,0 +1,96 @@
+/// Failures stopping the application, reported by `main` with a hint to fix them
+#[derive(Debug)]
+pub enum Error {
+    ConfigRead(String, std::io::Error),
+    ConfigParse(String, toml::de::Error),
+    MonitorNotFound(String),
+    Window(winit::error::OsError),
+    Surface(wgpu::CreateSurfaceError),
+    AdapterNotFound,
+    AdapterUnsupported(String),
+    Device(wgpu::RequestDeviceError),
+    PicturePathNotFound(String),
+    PictureCache(String, std::io::Error),
+    UnsplashAccessKey,
+    Eink(String, Box<dyn std::error::Error>),
+    Drm(String, Box<dyn std::error::Error>),
+    Image(String, image::ImageError),
+    InvalidFormat(String),
+    CountdownTarget(String),
+    AlreadyRunning(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConfigRead(path, err) => {
+                write!(f, "failed to read config {}: {}", path, err)
+            }
+            Self::ConfigParse(path, err) => {
+                write!(f, "invalid config {}: {}", path, err)
+            }
+            Self::MonitorNotFound(selector) => write!(
+                f,
+                "monitor {:?} not found, give an index from 0 or a name of connected monitor",
+                selector
+            ),
+            Self::Window(err) => write!(f, "failed to open window: {}", err),
+            Self::Surface(err) => write!(
+                f,
+                "failed to create surface: {}, try another --gpu-backend",
+                err
+            ),
+            Self::AdapterNotFound => write!(
+                f,
+                "no GPU adapter found, check `clock-app list-adapters` and graphics drivers, or try --gpu-backend gl for Mesa llvmpipe"
+            ),
+            Self::AdapterUnsupported(name) => write!(
+                f,
+                "adapter {} cannot present to the window, try another --adapter or --gpu-backend",
+                name
+            ),
+            Self::Device(err) => write!(
+                f,
+                "failed to create device: {}, try another --adapter or --gpu-backend",
+                err
+            ),
+            Self::PicturePathNotFound(path) => write!(
+                f,
+                "picture path {} not found, give a directory, manifest or URL by --picture-path",
+                path
+            ),
+            Self::PictureCache(path, err) => write!(
+                f,
+                "failed to create picture cache {}: {}, check --picture-cache",
+                path, err
+            ),
+            Self::UnsplashAccessKey => {
+                write!(f, "--unsplash-query needs --unsplash-access-key")
+            }
+            Self::Eink(path, err) => write!(f, "failed to open e-ink output {}: {}", path, err),
+            Self::Drm(path, err) => write!(
+                f,
+                "failed to open drm output {}: {}, stop the display server or add the user to video group",
+                path, err
+            ),
+            Self::Image(path, err) => write!(f, "failed to save {}: {}", path, err),
+            Self::InvalidFormat(format) => write!(
+                f,
+                "invalid format string {:?}, see strftime specifiers of chrono",
+                format
+            ),
+            Self::CountdownTarget(target) => write!(
+                f,
+                "countdown target {} does not exist in the timezone, skipped by daylight saving time",
+                target
+            ),
+            Self::AlreadyRunning(path) => write!(
+                f,
+                "another instance answers on control socket {}, stop it or leave out --control-socket",
+                path
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,856 @@
+/// Size of a cell of digit sprite sheets built from the font [px]
+const DIGIT_CELL_SIZE: (u32, u32) = (96, 144);
+
+/// Look of time element
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Face {
+    /// Time text in font
+    Text,
+    /// Binary coded decimal dots, a column per digit
+    Binary,
+    /// Words of time lit in a letter grid, with a dot per minute past five
+    Word,
+    /// Digits glowing in nixie tubes
+    Nixie,
+    /// Split-flap cards flipping over on change of digits
+    Flip,
+    /// Glowing segments of LED display
+    Segment,
+}
+
+impl Face {
+    /// Height of time drawn at text size [px]
+    pub fn height(self, size: f32) -> f32 {
+        match self {
+            Self::Text => size,
+            Self::Binary => size * 1.04,
+            // letter grid and the corner dots below it
+            Self::Word => size * 3.4,
+            Self::Nixie => size * 1.4,
+            Self::Flip => size * 1.2,
+            Self::Segment => size,
+        }
+    }
+
+    /// Face following this one, back to the first after the last
+    pub fn next(self) -> Self {
+        use clap::ValueEnum;
+
+        let faces = Self::value_variants();
+        let index = faces.iter().position(|face| *face == self).unwrap();
+        faces[(index + 1) % faces.len()]
+    }
+}
+
+/// Language of letter grid of word face
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordLanguage {
+    /// English
+    En,
+    /// German
+    De,
+    /// Dutch
+    Nl,
+}
+
+impl WordLanguage {
+    fn grid(self) -> [&'static str; 10] {
+        match self {
+            Self::En => [
+                "ITLISASAMPM",
+                "ACQUARTERDC",
+                "TWENTYFIVEX",
+                "HALFSTENFTO",
+                "PASTERUNINE",
+                "ONESIXTHREE",
+                "FOURFIVETWO",
+                "EIGHTELEVEN",
+                "SEVENTWELVE",
+                "TENSEOCLOCK",
+            ],
+            Self::De => [
+                "ESKISTAFÜNF",
+                "ZEHNZWANZIG",
+                "DREIVIERTEL",
+                "VORFUNKNACH",
+                "HALBAELFÜNF",
+                "EINSXAMZWEI",
+                "DREIPMJVIER",
+                "SECHSNLACHT",
+                "SIEBENZWÖLF",
+                "ZEHNEUNKUHR",
+            ],
+            Self::Nl => [
+                "HETKISAVIJF",
+                "TIENBTZVOOR",
+                "OVERMEKWART",
+                "HALFSPWOVER",
+                "VOORTHGEENS",
+                "TWEEAMCDRIE",
+                "VIERVIJFZES",
+                "ZEVENONEGEN",
+                "ACHTTIENELF",
+                "TWAALFPMUUR",
+            ],
+        }
+    }
+
+    /// Words read for time rounded down to five minutes, with rows they are found in
+    fn words(self, hour: u32, minute: u32) -> Vec<(usize, &'static str)> {
+        let step = minute / 5;
+        match self {
+            Self::En => {
+                let hours = [
+                    (8, "TWELVE"),
+                    (5, "ONE"),
+                    (6, "TWO"),
+                    (5, "THREE"),
+                    (6, "FOUR"),
+                    (6, "FIVE"),
+                    (5, "SIX"),
+                    (8, "SEVEN"),
+                    (7, "EIGHT"),
+                    (4, "NINE"),
+                    (9, "TEN"),
+                    (7, "ELEVEN"),
+                ];
+                let mut words = vec![(0, "IT"), (0, "IS")];
+                words.extend_from_slice(match step {
+                    0 => &[],
+                    1 => &[(2, "FIVE"), (4, "PAST")],
+                    2 => &[(3, "TEN"), (4, "PAST")],
+                    3 => &[(1, "A"), (1, "QUARTER"), (4, "PAST")],
+                    4 => &[(2, "TWENTY"), (4, "PAST")],
+                    5 => &[(2, "TWENTY"), (2, "FIVE"), (4, "PAST")],
+                    6 => &[(3, "HALF"), (4, "PAST")],
+                    7 => &[(2, "TWENTY"), (2, "FIVE"), (3, "TO")],
+                    8 => &[(2, "TWENTY"), (3, "TO")],
+                    9 => &[(1, "A"), (1, "QUARTER"), (3, "TO")],
+                    10 => &[(3, "TEN"), (3, "TO")],
+                    _ => &[(2, "FIVE"), (3, "TO")],
+                });
+                let hour = if step < 7 { hour } else { hour + 1 };
+                words.push(hours[hour as usize % 12]);
+                if step == 0 {
+                    words.push((9, "OCLOCK"));
+                }
+                words
+            }
+            Self::De => {
+                let hours = [
+                    (8, "ZWÖLF"),
+                    (5, "EINS"),
+                    (5, "ZWEI"),
+                    (6, "DREI"),
+                    (6, "VIER"),
+                    (4, "FÜNF"),
+                    (7, "SECHS"),
+                    (8, "SIEBEN"),
+                    (7, "ACHT"),
+                    (9, "NEUN"),
+                    (9, "ZEHN"),
+                    (4, "ELF"),
+                ];
+                let mut words = vec![(0, "ES"), (0, "IST")];
+                words.extend_from_slice(match step {
+                    0 => &[],
+                    1 => &[(0, "FÜNF"), (3, "NACH")],
+                    2 => &[(1, "ZEHN"), (3, "NACH")],
+                    3 => &[(2, "VIERTEL"), (3, "NACH")],
+                    4 => &[(1, "ZWANZIG"), (3, "NACH")],
+                    5 => &[(0, "FÜNF"), (3, "VOR"), (4, "HALB")],
+                    6 => &[(4, "HALB")],
+                    7 => &[(0, "FÜNF"), (3, "NACH"), (4, "HALB")],
+                    8 => &[(1, "ZWANZIG"), (3, "VOR")],
+                    9 => &[(2, "VIERTEL"), (3, "VOR")],
+                    10 => &[(1, "ZEHN"), (3, "VOR")],
+                    _ => &[(0, "FÜNF"), (3, "VOR")],
+                });
+                let hour = if step < 5 { hour } else { hour + 1 };
+                match (hour % 12, step) {
+                    // one o'clock is read "ein Uhr"
+                    (1, 0) => words.push((5, "EIN")),
+                    (hour, _) => words.push(hours[hour as usize]),
+                }
+                if step == 0 {
+                    words.push((9, "UHR"));
+                }
+                words
+            }
+            Self::Nl => {
+                let hours = [
+                    (9, "TWAALF"),
+                    (4, "EEN"),
+                    (5, "TWEE"),
+                    (5, "DRIE"),
+                    (6, "VIER"),
+                    (6, "VIJF"),
+                    (6, "ZES"),
+                    (7, "ZEVEN"),
+                    (8, "ACHT"),
+                    (7, "NEGEN"),
+                    (8, "TIEN"),
+                    (8, "ELF"),
+                ];
+                let mut words = vec![(0, "HET"), (0, "IS")];
+                words.extend_from_slice(match step {
+                    0 => &[],
+                    1 => &[(0, "VIJF"), (2, "OVER")],
+                    2 => &[(1, "TIEN"), (2, "OVER")],
+                    3 => &[(2, "KWART"), (3, "OVER")],
+                    4 => &[(1, "TIEN"), (1, "VOOR"), (3, "HALF")],
+                    5 => &[(0, "VIJF"), (1, "VOOR"), (3, "HALF")],
+                    6 => &[(3, "HALF")],
+                    7 => &[(0, "VIJF"), (2, "OVER"), (3, "HALF")],
+                    8 => &[(1, "TIEN"), (2, "OVER"), (3, "HALF")],
+                    9 => &[(2, "KWART"), (4, "VOOR")],
+                    10 => &[(1, "TIEN"), (1, "VOOR")],
+                    _ => &[(0, "VIJF"), (1, "VOOR")],
+                });
+                let hour = if step < 4 { hour } else { hour + 1 };
+                words.push(hours[hour as usize % 12]);
+                if step == 0 {
+                    words.push((9, "UUR"));
+                }
+                words
+            }
+        }
+    }
+}
+
+/// Duration of flip of a card [ms]
+const FLIP_DURATION: u64 = 300;
+
+/// Card of flip face, flipping over from the previous character since it started
+struct Card {
+    from: char,
+    to: char,
+    started: Option<web_time::Instant>,
+}
+
+/// Segments lit for digits 0 to 9, bits from a (top) to g (middle) clockwise
+const SEGMENTS: [u8; 10] = [0x3f, 0x06, 0x5b, 0x4f, 0x66, 0x6d, 0x7d, 0x07, 0x7f, 0x6f];
+
+/// Letter of word face drawn by glyph brush, centered at position [px]
+pub struct Letter {
+    pub letter: char,
+    pub position: (f32, f32),
+    pub size: f32,
+    pub lit: bool,
+}
+
+/// Draws digits of time text procedurally in faces other than text
+pub struct FacePipeline {
+    face: Face,
+    word_language: WordLanguage,
+    quad_pipeline: crate::quad::QuadPipeline,
+    nixie_pipeline: Option<crate::nixie::NixiePipeline>,
+    flip_pipeline: Option<crate::flip::FlipPipeline>,
+    cards: Vec<Card>,
+    /// Colors of lit and unlit segments in linear space, following text color if not given
+    segment_color: Option<[f32; 4]>,
+    segment_off_color: Option<[f32; 4]>,
+    segment_glow: f32,
+}
+
+impl FacePipeline {
+    /// `None` for text face, which is drawn by glyph brush
+    pub fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+        font: &wgpu_glyph::ab_glyph::FontArc,
+        config: &crate::config::Config,
+    ) -> Option<Self> {
+        if config.face == Face::Text {
+            return None;
+        }
+        let nixie_pipeline = (config.face == Face::Nixie).then(|| {
+            crate::nixie::NixiePipeline::new(
+                device,
+                target_format,
+                sample_count,
+                font,
+                config.nixie_sprites.as_deref(),
+            )
+        });
+        let flip_pipeline = (config.face == Face::Flip)
+            .then(|| crate::flip::FlipPipeline::new(device, target_format, sample_count, font));
+        Some(Self {
+            face: config.face,
+            word_language: config.word_language,
+            quad_pipeline: crate::quad::QuadPipeline::new(device, target_format, sample_count),
+            nixie_pipeline,
+            flip_pipeline,
+            cards: vec![],
+            segment_color: config
+                .segment_color
+                .map(|color| crate::text::linear_color(color.0)),
+            segment_off_color: config
+                .segment_off_color
+                .map(|color| crate::text::linear_color(color.0)),
+            segment_glow: config.segment_glow,
+        })
+    }
+
+    /// Queues time text placed at position [px] by anchor, scaled by text size [px], returning
+    /// letters left to be drawn with the font, with colons dimmed unless shown
+    #[allow(clippy::too_many_arguments)]
+    pub fn queue(
+        &mut self,
+        queue: &wgpu::Queue,
+        text: &str,
+        position: (f32, f32),
+        size: f32,
+        anchor: crate::text::Anchor,
+        color: [f32; 4],
+        target_size: (u32, u32),
+        colon_shown: bool,
+    ) -> Vec<Letter> {
+        match self.face {
+            Face::Text => vec![],
+            Face::Binary => {
+                self.queue_binary(text, position, size, anchor, color, target_size);
+                vec![]
+            }
+            Face::Word => self.queue_word(text, position, size, anchor, color, target_size),
+            Face::Nixie => {
+                self.queue_nixie(
+                    queue,
+                    text,
+                    position,
+                    size,
+                    anchor,
+                    color,
+                    target_size,
+                    colon_shown,
+                );
+                vec![]
+            }
+            Face::Flip => {
+                self.queue_flip(
+                    queue,
+                    text,
+                    position,
+                    size,
+                    anchor,
+                    color,
+                    target_size,
+                    colon_shown,
+                );
+                vec![]
+            }
+            Face::Segment => {
+                self.queue_segment(
+                    text,
+                    position,
+                    size,
+                    anchor,
+                    color,
+                    target_size,
+                    colon_shown,
+                );
+                vec![]
+            }
+        }
+    }
+
+    fn queue_binary(
+        &mut self,
+        text: &str,
+        position: (f32, f32),
+        size: f32,
+        anchor: crate::text::Anchor,
+        color: [f32; 4],
+        target_size: (u32, u32),
+    ) {
+        let digits = text
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .collect::<Vec<_>>();
+        let dot = size * 0.2;
+        let step = dot * 1.4;
+        // digits are paired as hours, minutes and seconds
+        let group_gap = dot * 0.6;
+        let groups = digits.len().div_ceil(2);
+        let width =
+            digits.len() as f32 * step - (step - dot) + groups.saturating_sub(1) as f32 * group_gap;
+        let height = 4.0 * step - (step - dot);
+        let (left, top) = anchor.place(position, (width, height));
+
+        let [r, g, b, a] = color;
+        for (i, digit) in digits.iter().enumerate() {
+            let x = left + i as f32 * step + (i / 2) as f32 * group_gap;
+            for bit in 0..4 {
+                let y = top + (3 - bit) as f32 * step;
+                let on = digit & (1 << bit) != 0;
+                let alpha = if on { a } else { a * 0.15 };
+                self.quad_pipeline.queue(
+                    [x, y, x + dot, y + dot],
+                    dot * 0.5,
+                    0.0,
+                    [r, g, b, alpha],
+                    target_size,
+                );
+            }
+        }
+    }
+
+    fn queue_word(
+        &mut self,
+        text: &str,
+        position: (f32, f32),
+        size: f32,
+        anchor: crate::text::Anchor,
+        color: [f32; 4],
+        target_size: (u32, u32),
+    ) -> Vec<Letter> {
+        // hour and minute are the first two numbers of time text
+        let mut numbers = text
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|number| number.parse::<u32>().ok());
+        let (Some(hour), Some(minute)) = (numbers.next(), numbers.next()) else {
+            return vec![];
+        };
+
+        let grid = self
+            .word_language
+            .grid()
+            .map(|row| row.chars().collect::<Vec<_>>());
+        let mut lit = vec![vec![false; grid[0].len()]; grid.len()];
+        let mut cursor = (0, 0);
+        for (row, word) in self.word_language.words(hour, minute) {
+            // words sharing a row are found left to right
+            let start = if cursor.0 == row { cursor.1 } else { 0 };
+            let word = word.chars().collect::<Vec<_>>();
+            let found = (start..=grid[row].len().saturating_sub(word.len()))
+                .find(|&col| grid[row][col..col + word.len()] == word[..]);
+            match found {
+                Some(col) => {
+                    lit[row][col..col + word.len()].fill(true);
+                    cursor = (row, col + word.len());
+                }
+                None => log::warn!("word {:?} is missing in row {}", word, row),
+            }
+        }
+
+        let step = size * 0.3;
+        let width = grid[0].len() as f32 * step;
+        let height = grid.len() as f32 * step;
+        let (left, top) = anchor.place(position, (width, height));
+
+        // minutes past five are dots at the corners, clockwise from top left
+        let [r, g, b, a] = color;
+        let dot = step * 0.2;
+        let corners = [
+            (left - step * 0.5, top - step * 0.5),
+            (left + width + step * 0.5, top - step * 0.5),
+            (left + width + step * 0.5, top + height + step * 0.5),
+            (left - step * 0.5, top + height + step * 0.5),
+        ];
+        for (i, (x, y)) in corners.into_iter().enumerate() {
+            let alpha = if (i as u32) < minute % 5 { a } else { a * 0.15 };
+            self.quad_pipeline.queue(
+                [x - dot * 0.5, y - dot * 0.5, x + dot * 0.5, y + dot * 0.5],
+                dot * 0.5,
+                0.0,
+                [r, g, b, alpha],
+                target_size,
+            );
+        }
+
+        grid.iter()
+            .zip(&lit)
+            .enumerate()
+            .flat_map(|(row, (letters, lit))| {
+                letters
+                    .iter()
+                    .zip(lit)
+                    .enumerate()
+                    .map(move |(col, (&letter, &lit))| Letter {
+                        letter,
+                        position: (
+                            left + (col as f32 + 0.5) * step,
+                            top + (row as f32 + 0.5) * step,
+                        ),
+                        size: step * 0.8,
+                        lit,
+                    })
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn queue_nixie(
+        &mut self,
+        queue: &wgpu::Queue,
+        text: &str,
+        position: (f32, f32),
+        size: f32,
+        anchor: crate::text::Anchor,
+        color: [f32; 4],
+        target_size: (u32, u32),
+        colon_shown: bool,
+    ) {
+        let nixie_pipeline = self.nixie_pipeline.as_mut().unwrap();
+        let height = size * 1.4;
+        let tube = height / nixie_pipeline.aspect();
+        let gap = size * 0.25;
+        let width = text
+            .chars()
+            .map(|c| if c.is_ascii_digit() { tube } else { gap })
+            .sum::<f32>();
+        let (mut left, top) = anchor.place(position, (width, height));
+
+        let alpha = color[3];
+        for c in text.chars() {
+            match c.to_digit(10) {
+                Some(digit) => {
+                    let rect = [left, top, left + tube, top + height];
+                    nixie_pipeline.queue(queue, digit, rect, alpha, target_size);
+                    left += tube;
+                }
+                None => {
+                    // separators are round neon lamps, going out while blinking
+                    if c == ':' && colon_shown {
+                        let dot = size * 0.06;
+                        let x = left + gap * 0.5;
+                        for y in [top + height * 0.38, top + height * 0.62] {
+                            self.quad_pipeline.queue(
+                                [x - dot, y - dot, x + dot, y + dot],
+                                dot,
+                                dot * 2.0,
+                                [1.0, 0.3, 0.04, alpha],
+                                target_size,
+                            );
+                        }
+                    }
+                    left += gap;
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn queue_flip(
+        &mut self,
+        queue: &wgpu::Queue,
+        text: &str,
+        position: (f32, f32),
+        size: f32,
+        anchor: crate::text::Anchor,
+        color: [f32; 4],
+        target_size: (u32, u32),
+        colon_shown: bool,
+    ) {
+        let now = web_time::Instant::now();
+        let duration = std::time::Duration::from_millis(FLIP_DURATION);
+        let chars = text.chars().collect::<Vec<_>>();
+        if chars.len() != self.cards.len() {
+            // layout change such as switching to countdown is not animated
+            self.cards = chars
+                .iter()
+                .map(|&c| Card {
+                    from: c,
+                    to: c,
+                    started: None,
+                })
+                .collect();
+        }
+        for (card, &c) in self.cards.iter_mut().zip(&chars) {
+            if card.to != c {
+                *card = Card {
+                    from: card.to,
+                    to: c,
+                    started: Some(now),
+                };
+            }
+            if card
+                .started
+                .is_some_and(|started| duration <= now - started)
+            {
+                card.started = None;
+            }
+        }
+
+        let flip_pipeline = self.flip_pipeline.as_mut().unwrap();
+        let height = size * 1.2;
+        let card_width = height * 2.0 / 3.0;
+        let step = card_width * 1.08;
+        let gap = size * 0.25;
+        let width = chars
+            .iter()
+            .map(|c| if c.is_ascii_digit() { step } else { gap })
+            .sum::<f32>();
+        let (mut left, top) = anchor.place(position, (width, height));
+        let hinge = top + height * 0.5;
+        // halves are split by a thin line at the hinge
+        let line = height * 0.01;
+
+        let [r, g, b, a] = color;
+        let card = [0.02, 0.02, 0.02, a];
+        for card_state in &self.cards {
+            let (Some(from), Some(to)) = (card_state.from.to_digit(10), card_state.to.to_digit(10))
+            else {
+                if card_state.to == ':' && colon_shown {
+                    let dot = size * 0.05;
+                    let x = left + gap * 0.5;
+                    for y in [top + height * 0.35, top + height * 0.65] {
+                        self.quad_pipeline.queue(
+                            [x - dot, y - dot, x + dot, y + dot],
+                            dot * 0.3,
+                            0.0,
+                            color,
+                            target_size,
+                        );
+                    }
+                }
+                left += if card_state.to.is_ascii_digit() {
+                    step
+                } else {
+                    gap
+                };
+                continue;
+            };
+            let right = left + card_width;
+            let top_half = [left, top, right, hinge - line];
+            let bottom_half = [left, hinge + line, right, top + height];
+            match card_state.started {
+                None => {
+                    flip_pipeline.queue(queue, to, top_half, (0.0, 0.5), color, card, target_size);
+                    flip_pipeline.queue(
+                        queue,
+                        to,
+                        bottom_half,
+                        (0.5, 1.0),
+                        color,
+                        card,
+                        target_size,
+                    );
+                }
+                Some(started) => {
+                    // upper flap falls from top to hinge, showing the next digit behind, then
+                    // its back with the lower half of the next digit falls down to the bottom
+                    let progress = (now - started).as_secs_f32() / duration.as_secs_f32();
+                    let fold = (progress * std::f32::consts::PI).cos();
+                    let shade = 0.5 + 0.5 * fold.abs();
+                    let shaded = [r * shade, g * shade, b * shade, a];
+                    let shaded_card = [card[0] * shade, card[1] * shade, card[2] * shade, card[3]];
+                    flip_pipeline.queue(queue, to, top_half, (0.0, 0.5), color, card, target_size);
+                    flip_pipeline.queue(
+                        queue,
+                        from,
+                        bottom_half,
+                        (0.5, 1.0),
+                        color,
+                        card,
+                        target_size,
+                    );
+                    let half = hinge - line - top;
+                    if 0.0 < fold {
+                        let flap = [left, hinge - line - half * fold, right, hinge - line];
+                        let rows = (0.0, 0.5);
+                        flip_pipeline.queue(
+                            queue,
+                            from,
+                            flap,
+                            rows,
+                            shaded,
+                            shaded_card,
+                            target_size,
+                        );
+                    } else {
+                        let flap = [left, hinge + line, right, hinge + line - half * fold];
+                        let rows = (0.5, 1.0);
+                        flip_pipeline.queue(
+                            queue,
+                            to,
+                            flap,
+                            rows,
+                            shaded,
+                            shaded_card,
+                            target_size,
+                        );
+                    }
+                }
+            }
+            left += step;
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn queue_segment(
+        &mut self,
+        text: &str,
+        position: (f32, f32),
+        size: f32,
+        anchor: crate::text::Anchor,
+        color: [f32; 4],
+        target_size: (u32, u32),
+        colon_shown: bool,
+    ) {
+        let on = self.segment_color.unwrap_or(color);
+        let off = self
+            .segment_off_color
+            .unwrap_or([on[0], on[1], on[2], on[3] * 0.08]);
+        let digit_width = size * 0.5;
+        let thickness = size * 0.1;
+        let step = digit_width + thickness * 2.5;
+        let gap = thickness * 3.0;
+        let width = text
+            .chars()
+            .map(|c| if c.is_ascii_digit() { step } else { gap })
+            .sum::<f32>()
+            - thickness * 1.5;
+        let (mut left, top) = anchor.place(position, (width, size));
+        let (left_edge, top) = (left + thickness * 0.5, top + thickness * 0.5);
+
+        // unlit segments are queued first to stay behind the glow of lit ones
+        let mut lit = vec![];
+        let mut unlit = vec![];
+        left = left_edge;
+        for c in text.chars() {
+            let Some(digit) = c.to_digit(10) else {
+                if c == ':' {
+                    let x = left + gap * 0.5 - thickness * 0.75;
+                    // blinking colon goes dark like unlit segments
+                    let dots = if colon_shown { &mut lit } else { &mut unlit };
+                    for y in [top + size * 0.3, top + size * 0.6] {
+                        dots.push([x - thickness * 0.5, y, x + thickness * 0.5, y + thickness]);
+                    }
+                }
+                left += gap;
+                continue;
+            };
+            let right = left + digit_width;
+            let middle = top + (size - thickness) * 0.5;
+            let bottom = top + size - thickness;
+            let inset = thickness * 0.6;
+            let horizontal = |y: f32| {
+                [
+                    left + inset,
+                    y - thickness * 0.5,
+                    right - inset,
+                    y + thickness * 0.5,
+                ]
+            };
+            let vertical = |x: f32, y0: f32, y1: f32| {
+                [
+                    x - thickness * 0.5,
+                    y0 + inset,
+                    x + thickness * 0.5,
+                    y1 - inset,
+                ]
+            };
+            let segments = [
+                horizontal(top),
+                vertical(right, top, middle),
+                vertical(right, middle, bottom),
+                horizontal(bottom),
+                vertical(left, middle, bottom),
+                vertical(left, top, middle),
+                horizontal(middle),
+            ];
+            for (i, rect) in segments.into_iter().enumerate() {
+                if SEGMENTS[digit as usize] & (1 << i) != 0 {
+                    lit.push(rect);
+                } else {
+                    unlit.push(rect);
+                }
+            }
+            left += step;
+        }
+
+        let radius = thickness * 0.5;
+        for rect in unlit {
+            self.quad_pipeline
+                .queue(rect, radius, 0.0, off, target_size);
+        }
+        for rect in lit {
+            self.quad_pipeline
+                .queue(rect, radius, self.segment_glow, on, target_size);
+        }
+    }
+
+    /// Whether cards are flipping, which needs redraws until they settle
+    pub fn is_animating(&self) -> bool {
+        self.cards.iter().any(|card| card.started.is_some())
+    }
+
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        self.quad_pipeline.draw(device, view, encoder);
+        if let Some(nixie_pipeline) = &mut self.nixie_pipeline {
+            nixie_pipeline.draw(device, view, encoder);
+        }
+        if let Some(flip_pipeline) = &mut self.flip_pipeline {
+            flip_pipeline.draw(device, view, encoder);
+        }
+    }
+}
+
+/// Rasterizes digits 0 to 9 of the font centered in cells side by side, scaled relative to the
+/// cell height
+pub fn digit_sheet(font: &wgpu_glyph::ab_glyph::FontArc, scale: f32) -> image::RgbaImage {
+    use wgpu_glyph::ab_glyph::Font;
+
+    let (cell_width, cell_height) = DIGIT_CELL_SIZE;
+    let mut image = image::RgbaImage::new(cell_width * 10, cell_height);
+    for digit in 0..10 {
+        let c = char::from_digit(digit, 10).unwrap();
+        let glyph = font.glyph_id(c).with_scale(cell_height as f32 * scale);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        let left = cell_width.saturating_sub(bounds.width() as u32) / 2;
+        let top = cell_height.saturating_sub(bounds.height() as u32) / 2;
+        outlined.draw(|x, y, coverage| {
+            let (x, y) = (left + x, top + y);
+            if x < cell_width && y < cell_height {
+                let value = (coverage * 255.0) as u8;
+                let pixel = image::Rgba([value, value, value, value]);
+                image.put_pixel(digit * cell_width + x, y, pixel);
+            }
+        });
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_are_found_in_their_rows() {
+        use clap::ValueEnum;
+
+        for language in WordLanguage::value_variants() {
+            let grid = language.grid().map(|row| row.chars().collect::<Vec<_>>());
+            for hour in 0..12 {
+                for minute in (0..60).step_by(5) {
+                    let mut cursor = (0, 0);
+                    for (row, word) in language.words(hour, minute) {
+                        let start = if cursor.0 == row { cursor.1 } else { 0 };
+                        let chars = word.chars().collect::<Vec<_>>();
+                        let found = (start..=grid[row].len().saturating_sub(chars.len()))
+                            .find(|&col| grid[row][col..col + chars.len()] == chars[..]);
+                        let Some(col) = found else {
+                            panic!(
+                                "{} not found in row {} at {}:{:02}",
+                                word, row, hour, minute
+                            );
+                        };
+                        cursor = (row, col + chars.len());
+                    }
+                }
+            }
+        }
+    }
+}
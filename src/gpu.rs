@@ -0,0 +1,173 @@
+/// Brightness of 1.0 on scRGB surface [nits]
+pub const SDR_WHITE: f32 = 80.0;
+
+/// Presentation of frames, fifo waits for vertical blank with the lowest power and immediate
+/// shows frames at once with the lowest latency
+#[derive(Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresentMode {
+    Fifo,
+    Mailbox,
+    Immediate,
+}
+
+impl PresentMode {
+    pub fn to_wgpu(self) -> wgpu::PresentMode {
+        match self {
+            Self::Fifo => wgpu::PresentMode::Fifo,
+            Self::Mailbox => wgpu::PresentMode::Mailbox,
+            Self::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
+
+/// Graphics API used for rendering
+#[derive(Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Vulkan,
+    Gl,
+    Metal,
+    Dx12,
+}
+
+impl Backend {
+    pub fn to_wgpu(self) -> wgpu::Backends {
+        match self {
+            Self::Vulkan => wgpu::Backends::VULKAN,
+            Self::Gl => wgpu::Backends::GL,
+            Self::Metal => wgpu::Backends::METAL,
+            Self::Dx12 => wgpu::Backends::DX12,
+        }
+    }
+}
+
+pub fn instance(backend: Option<Backend>) -> wgpu::Instance {
+    wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: backend.map_or(wgpu::Backends::all(), Backend::to_wgpu),
+        ..Default::default()
+    })
+}
+
+/// Picks adapter by index or name of `list_adapters`, or lets wgpu choose one if not given,
+/// which is a software one (e.g. llvmpipe) only when no hardware one is found
+pub async fn select_adapter(
+    instance: &wgpu::Instance,
+    backend: Option<Backend>,
+    selector: Option<&str>,
+    surface: Option<&wgpu::Surface>,
+) -> Option<wgpu::Adapter> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(selector) = selector {
+        let backends = backend.map_or(wgpu::Backends::all(), Backend::to_wgpu);
+        let adapter = instance
+            .enumerate_adapters(backends)
+            .enumerate()
+            .find(|(index, adapter)| {
+                selector == index.to_string() || selector == adapter.get_info().name
+            })
+            .map(|(_, adapter)| adapter)?;
+        return Some(adapter);
+    }
+    // browsers list no adapters, so the one they give is used
+    #[cfg(target_arch = "wasm32")]
+    let _ = (backend, selector);
+    instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            compatible_surface: surface,
+            force_fallback_adapter: false,
+        })
+        .await
+}
+
+/// Prints adapters available for `--adapter`
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list_adapters(backend: Option<Backend>) {
+    let backends = backend.map_or(wgpu::Backends::all(), Backend::to_wgpu);
+    for (index, adapter) in instance(backend).enumerate_adapters(backends).enumerate() {
+        let info = adapter.get_info();
+        println!(
+            "{}: {} ({:?}, {:?})",
+            index, info.name, info.backend, info.device_type
+        );
+    }
+}
+
+/// Texture drawn with multiple samples per pixel, `None` without multisampling
+pub fn multisampled_view(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    (width, height): (u32, u32),
+    sample_count: u32,
+) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// Resolves multisampled texture into single-sampled one
+pub fn resolve(
+    encoder: &mut wgpu::CommandEncoder,
+    multisampled: &wgpu::TextureView,
+    output: &wgpu::TextureView,
+) {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: multisampled,
+            resolve_target: Some(output),
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Load,
+                store: false,
+            },
+        })],
+        depth_stencil_attachment: None,
+    });
+}
+
+/// Converts pixel of HDR surface (4 halfs) back into SDR, scaling white to 1.0
+pub fn hdr_to_srgb(data: &[u8], brightness: f32) -> image::Rgba<u8> {
+    let channel = |i: usize| half_to_f32(u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]));
+    let srgb = |c: f32| {
+        let c = (c / brightness).clamp(0.0, 1.0);
+        let c = if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (c * 255.0).round() as u8
+    };
+    image::Rgba([
+        srgb(channel(0)),
+        srgb(channel(1)),
+        srgb(channel(2)),
+        (channel(3).clamp(0.0, 1.0) * 255.0).round() as u8,
+    ])
+}
+
+fn half_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f => sign * f32::INFINITY,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
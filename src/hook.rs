@@ -0,0 +1,27 @@
+/// Runs the shell command in background, event details are passed as environment variables
+pub fn run(command: &str, envs: &[(&str, &str)]) {
+    log::debug!("run hook {:?}", command);
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let child = std::process::Command::new(shell)
+        .args([flag, command])
+        .envs(envs.iter().copied())
+        .spawn();
+    match child {
+        // wait in background so that finished process does not remain as zombie
+        Ok(mut child) => {
+            let command = command.to_string();
+            std::thread::spawn(move || match child.wait() {
+                Ok(status) if !status.success() => {
+                    log::warn!("hook {:?} exited with {}", command, status)
+                }
+                Ok(_) => {}
+                Err(err) => log::error!("failed to wait hook {:?}: {}", command, err),
+            });
+        }
+        Err(err) => log::error!("failed to run hook {:?}: {}", command, err),
+    }
+}
@@ -0,0 +1,90 @@
+/// Limit of request bodies, larger ones are refused [B]
+const BODY_SIZE: usize = 4096;
+
+/// Serves control API on the port, commands are handed over through the receiver
+pub fn spawn(
+    port: u16,
+    status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+) -> std::sync::mpsc::Receiver<crate::control::Command> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        log::debug!("start http server on port {}", port);
+        let server = match tiny_http::Server::http(("0.0.0.0", port)) {
+            Ok(server) => server,
+            Err(err) => {
+                log::error!("failed to start http server: {}", err);
+                return;
+            }
+        };
+        for mut request in server.incoming_requests() {
+            log::debug!("http request {} {}", request.method(), request.url());
+            if (request.method(), request.url()) == (&tiny_http::Method::Get, "/metrics") {
+                let content_type =
+                    tiny_http::Header::from_bytes("Content-Type", "text/plain; version=0.0.4")
+                        .unwrap();
+                let response = tiny_http::Response::from_string(crate::metrics::render())
+                    .with_header(content_type);
+                if let Err(err) = request.respond(response) {
+                    log::warn!("failed to respond http request: {}", err);
+                }
+                continue;
+            }
+            let response = match handle(&mut request) {
+                Ok(Some(command)) => {
+                    if sender.send(command).is_err() {
+                        break;
+                    }
+                    tiny_http::Response::from_string("ok")
+                }
+                Ok(None) => {
+                    let status = status.lock().unwrap().clone();
+                    let content_type =
+                        tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+                    tiny_http::Response::from_string(serde_json::to_string(&status).unwrap())
+                        .with_header(content_type)
+                }
+                Err(code) => tiny_http::Response::from_string("error").with_status_code(code),
+            };
+            if let Err(err) = request.respond(response) {
+                log::warn!("failed to respond http request: {}", err);
+            }
+        }
+    });
+    receiver
+}
+
+/// Command of the request, `None` for status query, or error status code
+fn handle(request: &mut tiny_http::Request) -> Result<Option<crate::control::Command>, u16> {
+    use crate::control::Command;
+    use tiny_http::Method;
+
+    let command = match (request.method(), request.url()) {
+        (Method::Get, "/status") => return Ok(None),
+        (Method::Post, "/picture/next") => Command::NextPicture,
+        (Method::Post, "/picture/previous") => Command::PreviousPicture,
+        (Method::Put, "/message") => Command::SetMessage(Some(read_body(request)?)),
+        (Method::Delete, "/message") => Command::SetMessage(None),
+        (Method::Put, "/profile") => Command::SetProfile(Some(read_body(request)?)),
+        (Method::Delete, "/profile") => Command::SetProfile(None),
+        (Method::Post, "/alarm") => Command::TriggerAlarm,
+        (Method::Delete, "/alarm") => Command::DismissAlarm,
+        _ => return Err(404),
+    };
+    Ok(Some(command))
+}
+
+/// Body of the request as text, or error status code if it is too large or not UTF-8
+fn read_body(request: &mut tiny_http::Request) -> Result<String, u16> {
+    use std::io::Read;
+
+    let mut body = vec![];
+    request
+        .as_reader()
+        .take(BODY_SIZE as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|_| 400_u16)?;
+    if BODY_SIZE < body.len() {
+        return Err(413);
+    }
+    String::from_utf8(body).map_err(|_| 400)
+}
@@ -0,0 +1,52 @@
+/// Keeps display and system awake while alive
+pub struct Inhibitor {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    _connection: zbus::blocking::Connection,
+}
+
+impl Inhibitor {
+    /// Inhibits through org.freedesktop.ScreenSaver, released when the connection is closed
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let connection = zbus::blocking::Connection::session()?;
+        let reply = connection.call_method(
+            Some("org.freedesktop.ScreenSaver"),
+            "/org/freedesktop/ScreenSaver",
+            Some("org.freedesktop.ScreenSaver"),
+            "Inhibit",
+            &("clock-app", "showing clock"),
+        )?;
+        let cookie: u32 = reply.body()?;
+        log::debug!("inhibit screensaver {}", cookie);
+        Ok(Self {
+            _connection: connection,
+        })
+    }
+
+    /// Inhibits through execution state of the calling thread, which must outlive the inhibitor
+    #[cfg(windows)]
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        use windows_sys::Win32::System::Power;
+
+        let flags = Power::ES_CONTINUOUS | Power::ES_DISPLAY_REQUIRED | Power::ES_SYSTEM_REQUIRED;
+        if unsafe { Power::SetThreadExecutionState(flags) } == 0 {
+            return Err("SetThreadExecutionState failed".into());
+        }
+        log::debug!("inhibit sleep");
+        Ok(Self {})
+    }
+
+    #[cfg(not(any(all(unix, not(target_os = "macos")), windows)))]
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Err("sleep inhibition is not supported on this platform".into())
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        use windows_sys::Win32::System::Power;
+
+        unsafe { Power::SetThreadExecutionState(Power::ES_CONTINUOUS) };
+    }
+}
@@ -0,0 +1,185 @@
+/// Keys bound to each action, overridable by `[keys]` table of configuration
+#[derive(Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Keymap {
+    pub stopwatch: Vec<Key>,
+    pub stopwatch_start: Vec<Key>,
+    pub stopwatch_reset: Vec<Key>,
+    pub next_picture: Vec<Key>,
+    pub next_profile: Vec<Key>,
+    pub fullscreen: Vec<Key>,
+    pub pause: Vec<Key>,
+    pub screenshot: Vec<Key>,
+    pub quit: Vec<Key>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use winit::event::VirtualKeyCode;
+
+        Self {
+            stopwatch: vec![Key(VirtualKeyCode::S)],
+            stopwatch_start: vec![Key(VirtualKeyCode::Space)],
+            stopwatch_reset: vec![Key(VirtualKeyCode::R)],
+            next_picture: vec![Key(VirtualKeyCode::N)],
+            next_profile: vec![Key(VirtualKeyCode::M)],
+            fullscreen: vec![Key(VirtualKeyCode::F)],
+            pause: vec![Key(VirtualKeyCode::P)],
+            screenshot: vec![Key(VirtualKeyCode::C)],
+            quit: vec![Key(VirtualKeyCode::Q), Key(VirtualKeyCode::Escape)],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Stopwatch,
+    StopwatchStart,
+    StopwatchReset,
+    NextPicture,
+    NextProfile,
+    Fullscreen,
+    Pause,
+    Screenshot,
+    Quit,
+}
+
+impl Keymap {
+    pub fn action(&self, keycode: winit::event::VirtualKeyCode) -> Option<Action> {
+        [
+            (&self.stopwatch, Action::Stopwatch),
+            (&self.stopwatch_start, Action::StopwatchStart),
+            (&self.stopwatch_reset, Action::StopwatchReset),
+            (&self.next_picture, Action::NextPicture),
+            (&self.next_profile, Action::NextProfile),
+            (&self.fullscreen, Action::Fullscreen),
+            (&self.pause, Action::Pause),
+            (&self.screenshot, Action::Screenshot),
+            (&self.quit, Action::Quit),
+        ]
+        .into_iter()
+        .find(|(keys, _)| keys.contains(&Key(keycode)))
+        .map(|(_, action)| action)
+    }
+}
+
+/// Key written as a letter, a digit, or a name (e.g. "n", "5", "escape", "f1")
+#[derive(Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Key(pub winit::event::VirtualKeyCode);
+
+impl std::str::FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use winit::event::VirtualKeyCode::*;
+
+        let keycode = match s.to_ascii_lowercase().as_str() {
+            "a" => A,
+            "b" => B,
+            "c" => C,
+            "d" => D,
+            "e" => E,
+            "f" => F,
+            "g" => G,
+            "h" => H,
+            "i" => I,
+            "j" => J,
+            "k" => K,
+            "l" => L,
+            "m" => M,
+            "n" => N,
+            "o" => O,
+            "p" => P,
+            "q" => Q,
+            "r" => R,
+            "s" => S,
+            "t" => T,
+            "u" => U,
+            "v" => V,
+            "w" => W,
+            "x" => X,
+            "y" => Y,
+            "z" => Z,
+            "0" => Key0,
+            "1" => Key1,
+            "2" => Key2,
+            "3" => Key3,
+            "4" => Key4,
+            "5" => Key5,
+            "6" => Key6,
+            "7" => Key7,
+            "8" => Key8,
+            "9" => Key9,
+            "f1" => F1,
+            "f2" => F2,
+            "f3" => F3,
+            "f4" => F4,
+            "f5" => F5,
+            "f6" => F6,
+            "f7" => F7,
+            "f8" => F8,
+            "f9" => F9,
+            "f10" => F10,
+            "f11" => F11,
+            "f12" => F12,
+            "space" => Space,
+            "escape" | "esc" => Escape,
+            "enter" | "return" => Return,
+            "tab" => Tab,
+            "backspace" => Back,
+            "delete" => Delete,
+            "left" => Left,
+            "right" => Right,
+            "up" => Up,
+            "down" => Down,
+            "home" => Home,
+            "end" => End,
+            "pageup" => PageUp,
+            "pagedown" => PageDown,
+            "printscreen" => Snapshot,
+            _ => return Err(format!("invalid key: {:?}", s)),
+        };
+        Ok(Self(keycode))
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_parsed_case_insensitively() {
+        use winit::event::VirtualKeyCode;
+
+        for (name, keycode) in [
+            ("a", VirtualKeyCode::A),
+            ("Z", VirtualKeyCode::Z),
+            ("0", VirtualKeyCode::Key0),
+            ("F12", VirtualKeyCode::F12),
+            ("Space", VirtualKeyCode::Space),
+            ("esc", VirtualKeyCode::Escape),
+            ("Escape", VirtualKeyCode::Escape),
+            ("return", VirtualKeyCode::Return),
+            ("PageDown", VirtualKeyCode::PageDown),
+            ("printscreen", VirtualKeyCode::Snapshot),
+        ] {
+            assert!(name.parse::<Key>() == Ok(Key(keycode)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn unknown_keys_are_refused() {
+        for name in ["", "aa", "f13", "ctrl"] {
+            assert!(name.parse::<Key>().is_err(), "{}", name);
+        }
+    }
+}
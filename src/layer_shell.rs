@@ -0,0 +1,232 @@
+use smithay_client_toolkit::shell::WaylandSurface;
+
+/// Background layer surface (wlr-layer-shell) sharing the Wayland connection of winit
+pub struct LayerWindow {
+    id: winit::window::WindowId,
+    proxy: winit::event_loop::EventLoopProxy<winit::window::WindowId>,
+    display: *mut std::ffi::c_void,
+    connection: wayland_client::Connection,
+    event_queue: wayland_client::EventQueue<State>,
+    state: State,
+    _idle_inhibitor: Option<IdleInhibitor>,
+}
+
+type IdleInhibitManager =
+    wayland_protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibit_manager_v1::ZwpIdleInhibitManagerV1;
+type IdleInhibitor =
+    wayland_protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1;
+
+struct State {
+    registry_state: smithay_client_toolkit::registry::RegistryState,
+    output_state: smithay_client_toolkit::output::OutputState,
+    layer: smithay_client_toolkit::shell::wlr_layer::LayerSurface,
+    size: Option<(u32, u32)>,
+    resized: bool,
+}
+
+impl LayerWindow {
+    pub fn new(
+        event_loop: &winit::event_loop::EventLoop<winit::window::WindowId>,
+        inhibit_idle: bool,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        use smithay_client_toolkit::shell::wlr_layer;
+        use wayland_client::Proxy;
+        use winit::platform::wayland::EventLoopWindowTargetExtWayland;
+
+        log::debug!("create layer surface");
+        let display = event_loop
+            .wayland_display()
+            .ok_or("not running on wayland")?;
+        let backend =
+            unsafe { wayland_backend::client::Backend::from_foreign_display(display as _) };
+        let connection = wayland_client::Connection::from_backend(backend);
+        let (globals, mut event_queue) = wayland_client::globals::registry_queue_init(&connection)?;
+        let qh = event_queue.handle();
+
+        let compositor = smithay_client_toolkit::compositor::CompositorState::bind(&globals, &qh)?;
+        let layer_shell = wlr_layer::LayerShell::bind(&globals, &qh)?;
+        let surface = compositor.create_surface(&qh);
+        let id = winit::window::WindowId::from(surface.id().as_ptr() as u64);
+        let layer = layer_shell.create_layer_surface(
+            &qh,
+            surface,
+            wlr_layer::Layer::Background,
+            Some("clock-app"),
+            None,
+        );
+        layer.set_anchor(wlr_layer::Anchor::all());
+        layer.set_exclusive_zone(-1);
+        layer.set_keyboard_interactivity(wlr_layer::KeyboardInteractivity::None);
+        layer.set_size(0, 0);
+        layer.commit();
+
+        let idle_inhibitor = inhibit_idle
+            .then(
+                || match globals.bind::<IdleInhibitManager, _, _>(&qh, 1..=1, ()) {
+                    Ok(manager) => Some(manager.create_inhibitor(layer.wl_surface(), &qh, ())),
+                    Err(err) => {
+                        log::warn!("failed to inhibit idle: {}", err);
+                        None
+                    }
+                },
+            )
+            .flatten();
+
+        let mut state = State {
+            registry_state: smithay_client_toolkit::registry::RegistryState::new(&globals),
+            output_state: smithay_client_toolkit::output::OutputState::new(&globals, &qh),
+            layer,
+            size: None,
+            resized: false,
+        };
+        // surface must not be drawn before the first configure
+        while state.size.is_none() {
+            event_queue.blocking_dispatch(&mut state)?;
+        }
+        state.resized = false;
+
+        Ok(Self {
+            id,
+            proxy: event_loop.create_proxy(),
+            display,
+            connection,
+            event_queue,
+            state,
+            _idle_inhibitor: idle_inhibitor,
+        })
+    }
+
+    pub fn id(&self) -> winit::window::WindowId {
+        self.id
+    }
+
+    pub fn inner_size(&self) -> winit::dpi::PhysicalSize<u32> {
+        let (width, height) = self.state.size.unwrap_or_default();
+        winit::dpi::PhysicalSize::new(width, height)
+    }
+
+    /// Layer surface has no redraw event of winit, so a user event stands for it
+    pub fn request_redraw(&self) {
+        let _ = self.proxy.send_event(self.id);
+    }
+
+    /// Handles events read by winit and returns the new size when configured
+    pub fn poll_resize(&mut self) -> Option<winit::dpi::PhysicalSize<u32>> {
+        if let Err(err) = self.event_queue.dispatch_pending(&mut self.state) {
+            log::error!("failed to dispatch layer surface events: {}", err);
+        }
+        if let Err(err) = self.connection.flush() {
+            log::error!("failed to flush wayland connection: {}", err);
+        }
+        std::mem::take(&mut self.state.resized).then(|| self.inner_size())
+    }
+}
+
+unsafe impl raw_window_handle::HasRawWindowHandle for LayerWindow {
+    fn raw_window_handle(&self) -> raw_window_handle::RawWindowHandle {
+        use wayland_client::Proxy;
+
+        let mut handle = raw_window_handle::WaylandWindowHandle::empty();
+        handle.surface = self.state.layer.wl_surface().id().as_ptr() as _;
+        raw_window_handle::RawWindowHandle::Wayland(handle)
+    }
+}
+
+unsafe impl raw_window_handle::HasRawDisplayHandle for LayerWindow {
+    fn raw_display_handle(&self) -> raw_window_handle::RawDisplayHandle {
+        let mut handle = raw_window_handle::WaylandDisplayHandle::empty();
+        handle.display = self.display;
+        raw_window_handle::RawDisplayHandle::Wayland(handle)
+    }
+}
+
+impl smithay_client_toolkit::shell::wlr_layer::LayerShellHandler for State {
+    fn closed(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _layer: &smithay_client_toolkit::shell::wlr_layer::LayerSurface,
+    ) {
+        log::warn!("layer surface is closed by compositor");
+    }
+
+    fn configure(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _layer: &smithay_client_toolkit::shell::wlr_layer::LayerSurface,
+        configure: smithay_client_toolkit::shell::wlr_layer::LayerSurfaceConfigure,
+        _serial: u32,
+    ) {
+        log::debug!("configure layer surface {:?}", configure.new_size);
+        if self.size != Some(configure.new_size) {
+            self.size = Some(configure.new_size);
+            self.resized = true;
+        }
+    }
+}
+
+impl smithay_client_toolkit::compositor::CompositorHandler for State {
+    fn scale_factor_changed(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _surface: &wayland_client::protocol::wl_surface::WlSurface,
+        _new_factor: i32,
+    ) {
+    }
+
+    fn frame(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _surface: &wayland_client::protocol::wl_surface::WlSurface,
+        _time: u32,
+    ) {
+    }
+}
+
+impl smithay_client_toolkit::output::OutputHandler for State {
+    fn output_state(&mut self) -> &mut smithay_client_toolkit::output::OutputState {
+        &mut self.output_state
+    }
+
+    fn new_output(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _output: wayland_client::protocol::wl_output::WlOutput,
+    ) {
+    }
+
+    fn update_output(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _output: wayland_client::protocol::wl_output::WlOutput,
+    ) {
+    }
+
+    fn output_destroyed(
+        &mut self,
+        _conn: &wayland_client::Connection,
+        _qh: &wayland_client::QueueHandle<Self>,
+        _output: wayland_client::protocol::wl_output::WlOutput,
+    ) {
+    }
+}
+
+impl smithay_client_toolkit::registry::ProvidesRegistryState for State {
+    fn registry(&mut self) -> &mut smithay_client_toolkit::registry::RegistryState {
+        &mut self.registry_state
+    }
+
+    smithay_client_toolkit::registry_handlers![smithay_client_toolkit::output::OutputState];
+}
+
+smithay_client_toolkit::delegate_compositor!(State);
+smithay_client_toolkit::delegate_output!(State);
+smithay_client_toolkit::delegate_layer!(State);
+smithay_client_toolkit::delegate_registry!(State);
+wayland_client::delegate_noop!(State: IdleInhibitManager);
+wayland_client::delegate_noop!(State: IdleInhibitor);
@@ -0,0 +1,122 @@
+/// Log file attached once the configuration is read
+static FILE: std::sync::Mutex<Option<LogFile>> = std::sync::Mutex::new(None);
+
+/// Writes to stderr as env_logger does, and into the log file if it is opened
+struct Logger {
+    stderr: env_logger::Logger,
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.stderr.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.stderr.matches(record) {
+            return;
+        }
+        self.stderr.log(record);
+        if let Some(file) = FILE.lock().unwrap().as_mut() {
+            file.write(&format!(
+                "[{} {} {}] {}\n",
+                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+/// Filtered by `RUST_LOG` as env_logger
+pub fn init() {
+    let stderr = env_logger::Builder::from_default_env().build();
+    log::set_max_level(stderr.filter());
+    log::set_boxed_logger(Box::new(Logger { stderr })).unwrap();
+}
+
+/// Also writes logs into the file, rotated when it exceeds the size or age
+pub fn open_file(path: &str, max_size: u64, max_age: std::time::Duration, keep: usize) {
+    match LogFile::open(path.into(), max_size, max_age, keep) {
+        Ok(file) => *FILE.lock().unwrap() = Some(file),
+        Err(err) => log::error!("failed to open log file {}: {}", path, err),
+    }
+}
+
+struct LogFile {
+    path: std::path::PathBuf,
+    file: std::fs::File,
+    size: u64,
+    created: std::time::SystemTime,
+    max_size: u64,
+    max_age: std::time::Duration,
+    /// Number of rotated files kept as `<path>.1`, `<path>.2`, ...
+    keep: usize,
+}
+
+impl LogFile {
+    fn open(
+        path: std::path::PathBuf,
+        max_size: u64,
+        max_age: std::time::Duration,
+        keep: usize,
+    ) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let metadata = file.metadata()?;
+        // file continued from previous run keeps its age
+        let created = metadata
+            .created()
+            .unwrap_or_else(|_| std::time::SystemTime::now());
+        Ok(Self {
+            path,
+            file,
+            size: metadata.len(),
+            created,
+            max_size,
+            max_age,
+            keep,
+        })
+    }
+
+    fn write(&mut self, line: &str) {
+        use std::io::Write;
+
+        let expired = self.created.elapsed().is_ok_and(|age| self.max_age <= age);
+        if 0 < self.size && (self.max_size < self.size + line.len() as u64 || expired) {
+            if let Err(err) = self.rotate() {
+                eprintln!("failed to rotate log file {}: {}", self.path.display(), err);
+            }
+        }
+        // errors are not logged, which would write into the file again
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let rotated = |index: usize| {
+            let mut path = self.path.clone().into_os_string();
+            path.push(format!(".{}", index));
+            std::path::PathBuf::from(path)
+        };
+        let _ = std::fs::remove_file(rotated(self.keep));
+        for index in (1..self.keep).rev() {
+            let _ = std::fs::rename(rotated(index), rotated(index + 1));
+        }
+        if 0 < self.keep {
+            std::fs::rename(&self.path, rotated(1))?;
+        } else {
+            std::fs::remove_file(&self.path)?;
+        }
+        *self = Self::open(self.path.clone(), self.max_size, self.max_age, self.keep)?;
+        self.created = std::time::SystemTime::now();
+        Ok(())
+    }
+}
@@ -1,207 +1,2817 @@
+// the web build runs the renderer alone, leaving workers and state of the native event loop unused
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
+mod alarm;
+#[cfg(not(target_arch = "wasm32"))]
+mod audio;
+#[cfg(not(target_arch = "wasm32"))]
+mod backlight;
+mod battery;
+mod blur;
+mod calendar;
+mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod control;
+#[cfg(not(target_arch = "wasm32"))]
+mod dbus;
+#[cfg(not(target_arch = "wasm32"))]
+mod drm;
+#[cfg(not(target_arch = "wasm32"))]
+mod eink;
+mod emoji;
+mod error;
+mod face;
+mod flip;
+mod gpu;
+#[cfg(not(target_arch = "wasm32"))]
+mod hook;
+#[cfg(not(target_arch = "wasm32"))]
+mod http;
+mod icon;
+#[cfg(not(target_arch = "wasm32"))]
+mod inhibit;
+mod keymap;
+#[cfg(all(feature = "layer-shell", not(target_arch = "wasm32")))]
+mod layer_shell;
+#[cfg(not(target_arch = "wasm32"))]
+mod logger;
+mod metrics;
+mod moon;
+#[cfg(not(target_arch = "wasm32"))]
+mod mqtt;
+mod night;
+mod nixie;
+#[cfg(not(target_arch = "wasm32"))]
+mod ntp;
+mod overlay;
+mod palette;
 mod picture;
+mod picture_store;
+mod profile;
+mod quad;
+mod scrim;
+mod shader;
+mod shaping;
+#[cfg(not(target_arch = "wasm32"))]
+mod socket;
+#[cfg(not(target_arch = "wasm32"))]
+mod stats;
+mod stopwatch;
+mod sun;
+#[cfg(not(target_arch = "wasm32"))]
+mod systemd;
 mod text;
+mod theme;
+mod unsplash;
+mod weather;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
-#[derive(clap::Parser)]
+#[derive(Clone, clap::Parser)]
 #[command(version, about)]
 struct Args {
-    /// Update interval [ms]
-    #[arg(long, default_value = "1000")]
-    update_interval: u64,
-    /// Window width
-    #[arg(long, default_value = "800")]
-    width: u32,
-    /// Window height
-    #[arg(long, default_value = "480")]
-    height: u32,
-    /// Picture width
-    #[arg(long, default_value = "800")]
-    picture_width: u32,
-    /// Picture height
-    #[arg(long, default_value = "480")]
-    picture_height: u32,
-    /// Path representing background picture directory
-    #[arg(long, default_value = "pictures")]
-    picture_path: String,
-    /// A time until shuffling background picture [s]
-    #[arg(long, default_value = "3600")]
-    picture_interval: u64,
+    #[command(subcommand)]
+    command: Option<Subcommand>,
+    /// Path representing configuration file
+    #[arg(long)]
+    config: Option<String>,
+    /// Render a single frame offscreen into the PNG file and exit, without opening a window
+    #[arg(long, value_name = "PATH")]
+    render_once: Option<String>,
+    /// Update interval aligned to wall clock [ms] [default: 1000]
+    #[arg(long)]
+    update_interval: Option<u64>,
+    /// Window width [default: 800, 360 for widget]
+    #[arg(long)]
+    width: Option<u32>,
+    /// Window height [default: 480, 120 for widget]
+    #[arg(long)]
+    height: Option<u32>,
+    /// Monitor showing the window, by index or name [default: window manager decides]
+    #[arg(long)]
+    monitor: Option<String>,
+    /// Open one window per connected monitor [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    all_monitors: Option<bool>,
+    /// Borderless fullscreen window without cursor [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    fullscreen: Option<bool>,
+    /// Small transparent always-on-top click-through window showing just the time [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    widget: Option<bool>,
+    /// Draw as wallpaper on background layer of wlr-layer-shell (needs layer-shell feature) [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    layer_shell: Option<bool>,
+    /// Transparent window background showing only text [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    transparent: Option<bool>,
+    /// Draw as wallpaper in a desktop type window covering the monitor on X11 [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    desktop: Option<bool>,
+    /// Presentation of frames, fifo (vsync, lowest power), mailbox or immediate (lowest latency)
+    /// [default: fifo]
+    #[arg(long, value_enum)]
+    present_mode: Option<gpu::PresentMode>,
+    /// Graphics API used for rendering [default: best available]
+    #[arg(long, value_enum)]
+    gpu_backend: Option<gpu::Backend>,
+    /// GPU adapter by index or name of `list-adapters` [default: low power adapter]
+    #[arg(long)]
+    adapter: Option<String>,
+    /// Use HDR surface if the display supports it, extending highlights of pictures [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    hdr: Option<bool>,
+    /// Brightness of white in HDR [nits] [default: 203]
+    #[arg(long)]
+    hdr_white: Option<f32>,
+    /// Brightness which highlights of pictures reach in HDR [nits] [default: 1000]
+    #[arg(long)]
+    hdr_peak: Option<f32>,
+    /// Samples per pixel antialiasing edges of texts and shapes, 1, 2 or 4 [default: 1]
+    #[arg(long)]
+    msaa: Option<u32>,
+    /// Maximum picture width kept in memory [default: 800]
+    #[arg(long)]
+    picture_width: Option<u32>,
+    /// Maximum picture height kept in memory [default: 480]
+    #[arg(long)]
+    picture_height: Option<u32>,
+    /// Path or URL representing background picture directory, picture, or manifest listing pictures [default: pictures]
+    #[arg(long)]
+    picture_path: Option<String>,
+    /// A time until shuffling background picture [s] [default: 3600]
+    #[arg(long)]
+    picture_interval: Option<u64>,
+    /// Path representing cache directory of loaded pictures [default: no cache]
+    #[arg(long)]
+    picture_cache: Option<String>,
+    /// Size of cached pictures and downloads, the oldest ones are removed beyond it [MiB]
+    /// [default: 512]
+    #[arg(long)]
+    picture_cache_size: Option<u64>,
+    /// A time of crossfade between background pictures [ms] [default: 1000]
+    #[arg(long)]
+    picture_transition: Option<u64>,
+    /// Path representing WGSL fragment shader drawn as background instead of pictures
+    #[arg(long)]
+    shader: Option<String>,
+    /// Order of background pictures [default: shuffle]
+    #[arg(long, value_enum)]
+    picture_order: Option<picture_store::PictureOrder>,
+    /// Placement of background pictures [default: cover]
+    #[arg(long, value_enum)]
+    picture_fit: Option<picture::PictureFit>,
+    /// Color around pictures not covering the window, as `#rrggbb` [default: #000000]
+    #[arg(long)]
+    letterbox_color: Option<text::Color>,
+    /// Radius of gaussian blur of background behind texts, 0 disables it [px] [default: 0]
+    #[arg(long)]
+    blur: Option<f32>,
+    /// Part of background blurred [default: whole]
+    #[arg(long, value_enum)]
+    blur_region: Option<blur::BlurRegion>,
+    /// Opacity of darkening gradient behind texts, 0 disables it (e.g. 0.6) [default: 0]
+    #[arg(long)]
+    scrim_strength: Option<f32>,
+    /// Edge darkened by the gradient [default: bottom]
+    #[arg(long, value_enum)]
+    scrim_position: Option<scrim::ScrimPosition>,
+    /// Show title and date taken of pictures from sidecar `.txt` or metadata [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    caption: Option<bool>,
+    /// Maximum zoom of Ken Burns effect, 0 disables it (e.g. 0.2) [default: 0]
+    #[arg(long)]
+    ken_burns_zoom: Option<f32>,
+    /// A time of one zoom in and out cycle of Ken Burns effect [s] [default: 120]
+    #[arg(long)]
+    ken_burns_period: Option<u64>,
+    /// Query of random Unsplash photos used instead of picture path (e.g. "mountains")
+    #[arg(long)]
+    unsplash_query: Option<String>,
+    /// Access key of Unsplash API
+    #[arg(long)]
+    unsplash_access_key: Option<String>,
+    /// Number of Unsplash photos kept in cache for offline use [default: 10]
+    #[arg(long)]
+    unsplash_cache_size: Option<usize>,
+    /// Time format [default: 24h]
+    #[arg(long, value_enum)]
+    time_format: Option<text::TimeFormat>,
+    /// Format string of displayed text (e.g. "%H:%M:%S %a %d %b"), overriding time format
+    #[arg(long)]
+    format: Option<String>,
+    /// IANA timezone name (e.g. "Europe/Berlin") [default: system local time]
+    #[arg(long)]
+    timezone: Option<chrono_tz::Tz>,
+    /// Additional world clock (e.g. "tz=Asia/Tokyo,label=Tokyo"), repeatable
+    #[arg(long = "clock")]
+    clocks: Vec<text::Clock>,
+    /// Event counted down in days (e.g. "date=2025-12-25,label=Christmas", or "date=05-17" every
+    /// year), repeatable
+    #[arg(long = "days-until")]
+    days_until: Vec<text::DaysUntil>,
+    /// Show date line below the time [default: true]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    show_date: Option<bool>,
+    /// Format string of date line, `%V` gives ISO week number and `%j` day of year
+    /// [default: "%a %d %b %Y"]
+    #[arg(long)]
+    date_format: Option<String>,
+    /// Show ISO week number and day of year at the top right [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    show_week: Option<bool>,
+    /// Blink colons of time at 1Hz [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    blink_colon: Option<bool>,
+    /// Path representing TTF/OTF font of text [default: built-in Inconsolata Bold]
+    #[arg(long)]
+    font: Option<String>,
+    /// Path representing font of color emoji used for characters missing in font
+    /// (e.g. NotoColorEmoji.ttf)
+    #[arg(long)]
+    emoji_font: Option<String>,
+    /// Size of time text [px], date line is a quarter of it [default: 128]
+    #[arg(long)]
+    text_size: Option<f32>,
+    /// Horizontal position of time text relative to window width [default: 0.5]
+    #[arg(long)]
+    text_x: Option<f32>,
+    /// Vertical position of time text relative to window height [default: 0.5]
+    #[arg(long)]
+    text_y: Option<f32>,
+    /// Point of time text placed at text position [default: center]
+    #[arg(long, value_enum)]
+    text_anchor: Option<text::Anchor>,
+    /// How time is drawn [default: text]
+    #[arg(long, value_enum)]
+    face: Option<face::Face>,
+    /// Language of letter grid of word face [default: en]
+    #[arg(long, value_enum)]
+    word_language: Option<face::WordLanguage>,
+    /// Image of nixie face digits 0 to 9 side by side, whose brightness lights the tubes
+    #[arg(long)]
+    nixie_sprites: Option<String>,
+    /// Color of lit segments of segment face as #rrggbb or #rrggbbaa [default: text color]
+    #[arg(long)]
+    segment_color: Option<text::Color>,
+    /// Color of unlit segments of segment face [default: segment color at 8% opacity]
+    #[arg(long)]
+    segment_off_color: Option<text::Color>,
+    /// Radius of glow around lit segments of segment face, 0 disables it [px] [default: 12]
+    #[arg(long)]
+    segment_glow: Option<f32>,
+    /// Color of text as #rrggbb or #rrggbbaa [default: #ffffff]
+    #[arg(long)]
+    text_color: Option<text::Color>,
+    /// Width of text outline, 0 disables it [px] [default: 0]
+    #[arg(long)]
+    text_outline: Option<f32>,
+    /// Color of text outline [default: #000000]
+    #[arg(long)]
+    text_outline_color: Option<text::Color>,
+    /// Switch text to the dark color over bright parts of the picture [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    auto_contrast: Option<bool>,
+    /// Color of text over bright parts of the picture [default: #000000]
+    #[arg(long)]
+    text_dark_color: Option<text::Color>,
+    /// Color texts after dominant color of the picture [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    palette: Option<bool>,
+    /// Offset of text drop shadow, 0 disables it [px] [default: 0]
+    #[arg(long)]
+    text_shadow: Option<f32>,
+    /// Color of text drop shadow [default: #00000080]
+    #[arg(long)]
+    text_shadow_color: Option<text::Color>,
+    /// Text shown above time, replaced by greeting of the active theme
+    #[arg(long)]
+    greeting: Option<String>,
+    /// Profile used from start instead of the scheduled one
+    #[arg(long)]
+    profile: Option<String>,
+    /// Alarm time (e.g. "07:30", "07:30@weekdays", "07:30@sat,sun"), repeatable
+    #[arg(long = "alarm")]
+    alarms: Vec<alarm::Alarm>,
+    /// A time until ringing alarm stops [s] [default: 60]
+    #[arg(long)]
+    alarm_duration: Option<u64>,
+    /// Path representing sound file played on alarm
+    #[arg(long)]
+    alarm_sound: Option<String>,
+    /// Path representing sound file played on the hour
+    #[arg(long)]
+    chime: Option<String>,
+    /// Period silencing hourly chime (e.g. "22:00-07:00") [default: disabled]
+    #[arg(long)]
+    quiet_hours: Option<night::NightSchedule>,
+    /// Volume of sounds, 1.0 is original level [default: 1.0]
+    #[arg(long)]
+    volume: Option<f32>,
+    /// Shell command run on every hour
+    #[arg(long)]
+    on_hour: Option<String>,
+    /// Shell command run on picture change, given the picture as CLOCK_APP_PICTURE
+    #[arg(long)]
+    on_picture: Option<String>,
+    /// Shell command run when alarm rings
+    #[arg(long)]
+    on_alarm: Option<String>,
+    /// E-ink output instead of window, a framebuffer device (e.g. /dev/fb1) or a PNG file
+    #[arg(long)]
+    eink: Option<String>,
+    /// Gray levels of e-ink output, 2 is black and white [default: 2]
+    #[arg(long)]
+    eink_levels: Option<u8>,
+    /// Update interval of e-ink output aligned to wall clock [s] [default: 60]
+    #[arg(long)]
+    eink_interval: Option<u64>,
+    /// DRM/KMS output instead of window without display server, a device (e.g. /dev/dri/card0)
+    #[arg(long)]
+    drm: Option<String>,
+    /// Directory where screenshots are saved [default: .]
+    #[arg(long)]
+    screenshot_dir: Option<String>,
+    /// Target date and time of countdown (e.g. "2025-12-31T00:00")
+    #[arg(long)]
+    countdown: Option<text::Countdown>,
+    /// API key of OpenWeatherMap
+    #[arg(long)]
+    weather_api_key: Option<String>,
+    /// Location of weather (e.g. "Tokyo,JP")
+    #[arg(long)]
+    weather_location: Option<String>,
+    /// Units of weather temperature [default: metric]
+    #[arg(long, value_enum)]
+    weather_units: Option<weather::Units>,
+    /// A time until fetching weather [s] [default: 600]
+    #[arg(long)]
+    weather_interval: Option<u64>,
+    /// Path or URL representing ICS calendar, repeatable
+    #[arg(long = "calendar")]
+    calendars: Vec<String>,
+    /// A time until fetching calendars [s] [default: 900]
+    #[arg(long)]
+    calendar_interval: Option<u64>,
+    /// NTP server checking clock drift (e.g. "pool.ntp.org")
+    #[arg(long)]
+    ntp_server: Option<String>,
+    /// A time until querying NTP server [s] [default: 3600]
+    #[arg(long)]
+    ntp_interval: Option<u64>,
+    /// Clock drift showing indicator [s] [default: 1.0]
+    #[arg(long)]
+    ntp_threshold: Option<f64>,
+    /// Show CPU load, memory usage and temperature [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    stats: Option<bool>,
+    /// A time until sampling system stats [s] [default: 5]
+    #[arg(long)]
+    stats_interval: Option<u64>,
+    /// Show battery level [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    battery: Option<bool>,
+    /// A time until querying battery [s] [default: 60]
+    #[arg(long)]
+    battery_interval: Option<u64>,
+    /// Battery level showing warning color [%] [default: 20]
+    #[arg(long)]
+    battery_low: Option<u32>,
+    /// Warning color of low battery level [default: #ff4040]
+    #[arg(long)]
+    battery_low_color: Option<text::Color>,
+    /// Latitude of location showing sunrise and sunset times [deg]
+    #[arg(long, allow_negative_numbers = true)]
+    latitude: Option<f64>,
+    /// Longitude of location showing sunrise and sunset times, east is positive [deg]
+    #[arg(long, allow_negative_numbers = true)]
+    longitude: Option<f64>,
+    /// Warm background picture around sunrise and sunset [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    sun_tint: Option<bool>,
+    /// Show moon phase icon and name [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    moon: Option<bool>,
+    /// Period of night mode dimming output (e.g. "22:00-07:00") [default: disabled]
+    #[arg(long)]
+    night: Option<night::NightSchedule>,
+    /// Brightness of output in night mode [default: 0.3]
+    #[arg(long)]
+    night_brightness: Option<f32>,
+    /// Shift colors to red in night mode [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    night_red_shift: Option<bool>,
+    /// Display brightness to control, a sysfs backlight directory or "ddc" [default: disabled]
+    #[arg(long)]
+    backlight: Option<String>,
+    /// Display brightness outside night mode [%] [default: 100]
+    #[arg(long)]
+    backlight_day: Option<u32>,
+    /// Display brightness in night mode [%] [default: 20]
+    #[arg(long)]
+    backlight_night: Option<u32>,
+    /// A time until updating display brightness [s] [default: 60]
+    #[arg(long)]
+    backlight_interval: Option<u64>,
+    /// Illuminance file of light sensor driving display brightness instead of night mode
+    #[arg(long)]
+    ambient_light: Option<String>,
+    /// Illuminance of full display brightness [lx] [default: 500]
+    #[arg(long)]
+    ambient_light_max: Option<f32>,
+    /// Maximum shift of text against burn-in, 0 disables it [px] [default: 0]
+    #[arg(long)]
+    burn_in_shift: Option<f32>,
+    /// A time until shifting text against burn-in [s] [default: 300]
+    #[arg(long)]
+    burn_in_period: Option<u64>,
+    /// Shift background picture together with text [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    burn_in_picture: Option<bool>,
+    /// Prevent screensaver and sleep while running [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    inhibit_sleep: Option<bool>,
+    /// Port of HTTP control API [default: disabled]
+    #[arg(long)]
+    http_port: Option<u16>,
+    /// Host of MQTT broker [default: disabled]
+    #[arg(long)]
+    mqtt_host: Option<String>,
+    /// Port of MQTT broker [default: 1883]
+    #[arg(long)]
+    mqtt_port: Option<u16>,
+    /// Username of MQTT broker
+    #[arg(long)]
+    mqtt_username: Option<String>,
+    /// Password of MQTT broker
+    #[arg(long)]
+    mqtt_password: Option<String>,
+    /// Prefix of MQTT topics [default: clock-app]
+    #[arg(long)]
+    mqtt_topic: Option<String>,
+    /// Serve org.gossiperloturot.Clock on D-Bus session bus [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    dbus: Option<bool>,
+    /// Listen control socket for `ctl` subcommand [default: false]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    control_socket: Option<bool>,
+    /// File logs are written into besides stderr
+    #[arg(long)]
+    log_file: Option<String>,
+    /// Size the log file is rotated at [KiB] [default: 1024]
+    #[arg(long)]
+    log_max_size: Option<u64>,
+    /// Age the log file is rotated at [h] [default: 24]
+    #[arg(long)]
+    log_max_age: Option<u64>,
+    /// Number of rotated log files kept [default: 5]
+    #[arg(long)]
+    log_keep: Option<usize>,
+}
+
+#[derive(Clone, clap::Subcommand)]
+enum Subcommand {
+    /// Send a command to the running instance through control socket
+    Ctl {
+        /// One of next, previous, pause, resume, show-text <text>, clear-text, profile <name>,
+        /// alarm, dismiss, brightness <percent> or status
+        #[arg(required = true, trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+    /// Print GPU adapters available for `--adapter`
+    ListAdapters,
+}
+
+impl Args {
+    fn into_config(self) -> Result<config::Config, error::Error> {
+        let mut config = match self.config.as_deref() {
+            Some(path) => config::Config::load(path)?,
+            None => config::Config::default(),
+        };
+
+        if let Some(update_interval) = self.update_interval {
+            config.update_interval = update_interval;
+        }
+        if let Some(width) = self.width {
+            config.width = Some(width);
+        }
+        if let Some(height) = self.height {
+            config.height = Some(height);
+        }
+        if let Some(monitor) = self.monitor {
+            config.monitor = Some(monitor);
+        }
+        if let Some(all_monitors) = self.all_monitors {
+            config.all_monitors = all_monitors;
+        }
+        if let Some(fullscreen) = self.fullscreen {
+            config.fullscreen = fullscreen;
+        }
+        if let Some(widget) = self.widget {
+            config.widget = widget;
+        }
+        if let Some(transparent) = self.transparent {
+            config.transparent = transparent;
+        }
+        if let Some(layer_shell) = self.layer_shell {
+            config.layer_shell = layer_shell;
+        }
+        if let Some(desktop) = self.desktop {
+            config.desktop = desktop;
+        }
+        if let Some(present_mode) = self.present_mode {
+            config.present_mode = present_mode;
+        }
+        if let Some(gpu_backend) = self.gpu_backend {
+            config.gpu_backend = Some(gpu_backend);
+        }
+        if let Some(adapter) = self.adapter {
+            config.adapter = Some(adapter);
+        }
+        if let Some(hdr) = self.hdr {
+            config.hdr = hdr;
+        }
+        if let Some(hdr_white) = self.hdr_white {
+            config.hdr_white = hdr_white;
+        }
+        if let Some(hdr_peak) = self.hdr_peak {
+            config.hdr_peak = hdr_peak;
+        }
+        if let Some(msaa) = self.msaa {
+            config.msaa = msaa;
+        }
+        if let Some(picture_width) = self.picture_width {
+            config.picture_width = picture_width;
+        }
+        if let Some(picture_height) = self.picture_height {
+            config.picture_height = picture_height;
+        }
+        if let Some(picture_path) = self.picture_path {
+            config.picture_path = picture_path;
+        }
+        if let Some(picture_interval) = self.picture_interval {
+            config.picture_interval = picture_interval;
+        }
+        if let Some(picture_cache) = self.picture_cache {
+            config.picture_cache = Some(picture_cache);
+        }
+        if let Some(picture_cache_size) = self.picture_cache_size {
+            config.picture_cache_size = picture_cache_size;
+        }
+        if let Some(picture_transition) = self.picture_transition {
+            config.picture_transition = picture_transition;
+        }
+        if let Some(shader) = self.shader {
+            config.shader = Some(shader);
+        }
+        if let Some(picture_order) = self.picture_order {
+            config.picture_order = picture_order;
+        }
+        if let Some(picture_fit) = self.picture_fit {
+            config.picture_fit = picture_fit;
+        }
+        if let Some(letterbox_color) = self.letterbox_color {
+            config.letterbox_color = letterbox_color;
+        }
+        if let Some(blur) = self.blur {
+            config.blur = blur;
+        }
+        if let Some(blur_region) = self.blur_region {
+            config.blur_region = blur_region;
+        }
+        if let Some(scrim_strength) = self.scrim_strength {
+            config.scrim_strength = scrim_strength;
+        }
+        if let Some(scrim_position) = self.scrim_position {
+            config.scrim_position = scrim_position;
+        }
+        if let Some(caption) = self.caption {
+            config.caption = caption;
+        }
+        if let Some(ken_burns_zoom) = self.ken_burns_zoom {
+            config.ken_burns_zoom = ken_burns_zoom;
+        }
+        if let Some(ken_burns_period) = self.ken_burns_period {
+            config.ken_burns_period = ken_burns_period;
+        }
+        if let Some(unsplash_query) = self.unsplash_query {
+            config.unsplash_query = Some(unsplash_query);
+        }
+        if let Some(unsplash_access_key) = self.unsplash_access_key {
+            config.unsplash_access_key = Some(unsplash_access_key);
+        }
+        if let Some(unsplash_cache_size) = self.unsplash_cache_size {
+            config.unsplash_cache_size = unsplash_cache_size;
+        }
+        if let Some(time_format) = self.time_format {
+            config.time_format = time_format;
+        }
+        if let Some(format) = self.format {
+            config.format = Some(format);
+        }
+        if let Some(timezone) = self.timezone {
+            config.timezone = Some(timezone);
+        }
+        if !self.clocks.is_empty() {
+            config.clocks = self.clocks;
+        }
+        if !self.days_until.is_empty() {
+            config.days_until = self.days_until;
+        }
+        if let Some(show_date) = self.show_date {
+            config.show_date = show_date;
+        }
+        if let Some(date_format) = self.date_format {
+            config.date_format = date_format;
+        }
+        if let Some(show_week) = self.show_week {
+            config.show_week = show_week;
+        }
+        if let Some(blink_colon) = self.blink_colon {
+            config.blink_colon = blink_colon;
+        }
+        if let Some(font) = self.font {
+            config.font = Some(font);
+        }
+        if let Some(emoji_font) = self.emoji_font {
+            config.emoji_font = Some(emoji_font);
+        }
+        if let Some(text_size) = self.text_size {
+            config.text_size = text_size;
+        }
+        if let Some(text_x) = self.text_x {
+            config.text_x = text_x;
+        }
+        if let Some(text_y) = self.text_y {
+            config.text_y = text_y;
+        }
+        if let Some(text_anchor) = self.text_anchor {
+            config.text_anchor = text_anchor;
+        }
+        if let Some(face) = self.face {
+            config.face = face;
+        }
+        if let Some(word_language) = self.word_language {
+            config.word_language = word_language;
+        }
+        if let Some(nixie_sprites) = self.nixie_sprites {
+            config.nixie_sprites = Some(nixie_sprites);
+        }
+        if let Some(segment_color) = self.segment_color {
+            config.segment_color = Some(segment_color);
+        }
+        if let Some(segment_off_color) = self.segment_off_color {
+            config.segment_off_color = Some(segment_off_color);
+        }
+        if let Some(segment_glow) = self.segment_glow {
+            config.segment_glow = segment_glow;
+        }
+        if let Some(text_color) = self.text_color {
+            config.text_color = text_color;
+        }
+        if let Some(text_outline) = self.text_outline {
+            config.text_outline = text_outline;
+        }
+        if let Some(text_outline_color) = self.text_outline_color {
+            config.text_outline_color = text_outline_color;
+        }
+        if let Some(auto_contrast) = self.auto_contrast {
+            config.auto_contrast = auto_contrast;
+        }
+        if let Some(text_dark_color) = self.text_dark_color {
+            config.text_dark_color = text_dark_color;
+        }
+        if let Some(palette) = self.palette {
+            config.palette = palette;
+        }
+        if let Some(text_shadow) = self.text_shadow {
+            config.text_shadow = text_shadow;
+        }
+        if let Some(text_shadow_color) = self.text_shadow_color {
+            config.text_shadow_color = text_shadow_color;
+        }
+        if let Some(greeting) = self.greeting {
+            config.greeting = Some(greeting);
+        }
+        if let Some(profile) = self.profile {
+            config.profile = Some(profile);
+        }
+        if !self.alarms.is_empty() {
+            config.alarms = self.alarms;
+        }
+        if let Some(alarm_duration) = self.alarm_duration {
+            config.alarm_duration = alarm_duration;
+        }
+        if let Some(alarm_sound) = self.alarm_sound {
+            config.alarm_sound = Some(alarm_sound);
+        }
+        if let Some(chime) = self.chime {
+            config.chime = Some(chime);
+        }
+        if let Some(quiet_hours) = self.quiet_hours {
+            config.quiet_hours = Some(quiet_hours);
+        }
+        if let Some(volume) = self.volume {
+            config.volume = volume;
+        }
+        if let Some(on_hour) = self.on_hour {
+            config.on_hour = Some(on_hour);
+        }
+        if let Some(on_picture) = self.on_picture {
+            config.on_picture = Some(on_picture);
+        }
+        if let Some(on_alarm) = self.on_alarm {
+            config.on_alarm = Some(on_alarm);
+        }
+        if let Some(eink) = self.eink {
+            config.eink = Some(eink);
+        }
+        if let Some(eink_levels) = self.eink_levels {
+            config.eink_levels = eink_levels;
+        }
+        if let Some(eink_interval) = self.eink_interval {
+            config.eink_interval = eink_interval;
+        }
+        if let Some(drm) = self.drm {
+            config.drm = Some(drm);
+        }
+        if let Some(screenshot_dir) = self.screenshot_dir {
+            config.screenshot_dir = screenshot_dir;
+        }
+        if let Some(countdown) = self.countdown {
+            config.countdown = Some(countdown);
+        }
+        if let Some(weather_api_key) = self.weather_api_key {
+            config.weather_api_key = Some(weather_api_key);
+        }
+        if let Some(weather_location) = self.weather_location {
+            config.weather_location = Some(weather_location);
+        }
+        if let Some(weather_units) = self.weather_units {
+            config.weather_units = weather_units;
+        }
+        if let Some(weather_interval) = self.weather_interval {
+            config.weather_interval = weather_interval;
+        }
+        if !self.calendars.is_empty() {
+            config.calendars = self.calendars;
+        }
+        if let Some(calendar_interval) = self.calendar_interval {
+            config.calendar_interval = calendar_interval;
+        }
+        if let Some(ntp_server) = self.ntp_server {
+            config.ntp_server = Some(ntp_server);
+        }
+        if let Some(ntp_interval) = self.ntp_interval {
+            config.ntp_interval = ntp_interval;
+        }
+        if let Some(ntp_threshold) = self.ntp_threshold {
+            config.ntp_threshold = ntp_threshold;
+        }
+        if let Some(stats) = self.stats {
+            config.stats = stats;
+        }
+        if let Some(stats_interval) = self.stats_interval {
+            config.stats_interval = stats_interval;
+        }
+        if let Some(battery) = self.battery {
+            config.battery = battery;
+        }
+        if let Some(battery_interval) = self.battery_interval {
+            config.battery_interval = battery_interval;
+        }
+        if let Some(battery_low) = self.battery_low {
+            config.battery_low = battery_low;
+        }
+        if let Some(battery_low_color) = self.battery_low_color {
+            config.battery_low_color = battery_low_color;
+        }
+        if let Some(latitude) = self.latitude {
+            config.latitude = Some(latitude);
+        }
+        if let Some(longitude) = self.longitude {
+            config.longitude = Some(longitude);
+        }
+        if let Some(sun_tint) = self.sun_tint {
+            config.sun_tint = sun_tint;
+        }
+        if let Some(moon) = self.moon {
+            config.moon = moon;
+        }
+        if let Some(night) = self.night {
+            config.night = Some(night);
+        }
+        if let Some(night_brightness) = self.night_brightness {
+            config.night_brightness = night_brightness;
+        }
+        if let Some(night_red_shift) = self.night_red_shift {
+            config.night_red_shift = night_red_shift;
+        }
+        if let Some(backlight) = self.backlight {
+            config.backlight = Some(backlight);
+        }
+        if let Some(backlight_day) = self.backlight_day {
+            config.backlight_day = backlight_day;
+        }
+        if let Some(backlight_night) = self.backlight_night {
+            config.backlight_night = backlight_night;
+        }
+        if let Some(backlight_interval) = self.backlight_interval {
+            config.backlight_interval = backlight_interval;
+        }
+        if let Some(ambient_light) = self.ambient_light {
+            config.ambient_light = Some(ambient_light);
+        }
+        if let Some(ambient_light_max) = self.ambient_light_max {
+            config.ambient_light_max = ambient_light_max;
+        }
+        if let Some(burn_in_shift) = self.burn_in_shift {
+            config.burn_in_shift = burn_in_shift;
+        }
+        if let Some(burn_in_period) = self.burn_in_period {
+            config.burn_in_period = burn_in_period;
+        }
+        if let Some(burn_in_picture) = self.burn_in_picture {
+            config.burn_in_picture = burn_in_picture;
+        }
+        if let Some(inhibit_sleep) = self.inhibit_sleep {
+            config.inhibit_sleep = inhibit_sleep;
+        }
+        if let Some(http_port) = self.http_port {
+            config.http_port = Some(http_port);
+        }
+        if let Some(mqtt_host) = self.mqtt_host {
+            config.mqtt_host = Some(mqtt_host);
+        }
+        if let Some(mqtt_port) = self.mqtt_port {
+            config.mqtt_port = mqtt_port;
+        }
+        if let Some(mqtt_username) = self.mqtt_username {
+            config.mqtt_username = Some(mqtt_username);
+        }
+        if let Some(mqtt_password) = self.mqtt_password {
+            config.mqtt_password = Some(mqtt_password);
+        }
+        if let Some(mqtt_topic) = self.mqtt_topic {
+            config.mqtt_topic = mqtt_topic;
+        }
+        if let Some(dbus) = self.dbus {
+            config.dbus = dbus;
+        }
+        if let Some(control_socket) = self.control_socket {
+            config.control_socket = control_socket;
+        }
+        if let Some(log_file) = self.log_file {
+            config.log_file = Some(log_file);
+        }
+        if let Some(log_max_size) = self.log_max_size {
+            config.log_max_size = log_max_size;
+        }
+        if let Some(log_max_age) = self.log_max_age {
+            config.log_max_age = log_max_age;
+        }
+        if let Some(log_keep) = self.log_keep {
+            config.log_keep = log_keep;
+        }
+
+        if let Some(format) = text::invalid_format(&config) {
+            return Err(error::Error::InvalidFormat(format.to_string()));
+        }
+        if let Some(countdown) = config.countdown {
+            if countdown.target(config.timezone).is_none() {
+                return Err(error::Error::CountdownTarget(countdown.to_string()));
+            }
+        }
+
+        Ok(config)
+    }
 }
 
+/// Update interval while stopwatch is running or animating [ms]
+const ANIMATION_INTERVAL: u64 = 30;
+
+/// Center of weather icon relative to window size
+const WEATHER_ICON_CENTER: (f32, f32) = (0.9, 0.1);
+
+/// Size of weather icon [px]
+const WEATHER_ICON_SIZE: f32 = 96.0;
+
+/// Center of moon icon relative to window size
+const MOON_ICON_CENTER: (f32, f32) = (0.1, 0.1);
+
+/// Size of moon icon [px]
+const MOON_ICON_SIZE: f32 = 48.0;
+
+/// Change of moon phase redrawing moon icon, about an hour
+const MOON_PHASE_STEP: f64 = 0.0015;
+
+/// Horizontal travel of touch recognized as swipe, shorter ones are taps [px]
+const SWIPE_DISTANCE: f64 = 50.0;
+
+/// Time headless outputs wait for a picture being decoded, before drawing without it
+const PICTURE_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Time before switching pictures to upload the next one
+const PICTURE_PREFETCH: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() {
-    env_logger::init();
+    logger::init();
 
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    web::run();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run() -> Result<(), error::Error> {
     use clap::Parser;
-    let args = Args::parse();
+    let mut args = Args::parse();
+    match args.command.take() {
+        Some(Subcommand::Ctl { command }) => {
+            match socket::send(&command.join(" ")) {
+                Ok(response) => println!("{}", response),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Some(Subcommand::ListAdapters) => {
+            gpu::list_adapters(args.into_config()?.gpu_backend);
+            return Ok(());
+        }
+        None => {}
+    }
+    if let Some(path) = args.render_once.take() {
+        let config = args.into_config()?;
+        open_log_file(&config);
+        let profile = profile::active(&config, config.profile.as_deref());
+        return render_once(
+            theme::apply(&profile::apply(&config, profile), theme::active(&config)),
+            &path,
+        );
+    }
+    // flags are applied again over the file on reload
+    let reload_args = args.clone();
+    let mut base_config = args.into_config()?;
+    open_log_file(&base_config);
+    // profile chosen by key or remote control, otherwise the scheduled one is used
+    let mut chosen_profile = base_config.profile.clone();
+    let mut profile = profile::active(&base_config, chosen_profile.as_deref());
+    let mut theme = theme::active(&base_config);
+    let config = theme::apply(&profile::apply(&base_config, profile), theme);
+    if config.eink.is_some() || config.drm.is_some() {
+        return run_headless(base_config);
+    }
 
     log::debug!("start application");
-    let update_interval = std::time::Duration::from_millis(args.update_interval);
-    let event_loop = winit::event_loop::EventLoopBuilder::new().build();
-    let window = winit::window::WindowBuilder::new()
-        .with_inner_size(winit::dpi::PhysicalSize::new(args.width, args.height))
-        .build(&event_loop)
-        .unwrap();
-    let mut renderer = pollster::block_on(Renderer::new(
-        window,
-        args.picture_width,
-        args.picture_height,
-    ));
-    let picture_interval = std::time::Duration::from_secs(args.picture_interval);
-    let mut picture_interval_instance = std::time::Instant::now();
-    let pictures = load_pictures(&args.picture_path, args.picture_width, args.picture_height);
-
-    use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
-    renderer.set_picture(pictures.choose(&mut rng).unwrap());
+    let update_interval = std::time::Duration::from_millis(config.update_interval);
+    let mut tick_interval = tick_interval(&config);
+    // user events request redraw of windows not managed by winit
+    let event_loop = winit::event_loop::EventLoopBuilder::with_user_event().build();
+    let mut picture_interval = std::time::Duration::from_secs(config.picture_interval);
+    let config_watcher = reload_args
+        .config
+        .as_deref()
+        .and_then(|path| match config::watch(path) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                log::error!("failed to watch config {}: {}", path, err);
+                None
+            }
+        });
+
+    let mut windows = vec![];
+    if config.layer_shell {
+        #[cfg(feature = "layer-shell")]
+        match layer_shell::LayerWindow::new(&event_loop, config.inhibit_sleep) {
+            Ok(layer) => windows.push(Window::Layer(Box::new(layer))),
+            Err(err) => log::error!("failed to create layer surface: {}", err),
+        }
+        #[cfg(not(feature = "layer-shell"))]
+        log::warn!("layer-shell feature is disabled, open normal window instead");
+    }
+    if windows.is_empty() {
+        for monitor in select_monitors(&event_loop, &config)? {
+            let mut window_builder = winit::window::WindowBuilder::new()
+                .with_inner_size(winit::dpi::PhysicalSize::<u32>::from(config.size()));
+            if let Some(monitor) = &monitor {
+                log::debug!("open window on monitor {:?}", monitor.name());
+                window_builder = window_builder.with_position(monitor.position());
+            }
+            if config.fullscreen {
+                let fullscreen = winit::window::Fullscreen::Borderless(monitor.clone());
+                window_builder = window_builder
+                    .with_fullscreen(Some(fullscreen))
+                    .with_decorations(false);
+            }
+            if config.desktop {
+                window_builder = with_desktop_type(window_builder, &event_loop, monitor.as_ref());
+            }
+            if config.transparent || config.widget {
+                window_builder = window_builder.with_transparent(true);
+            }
+            if config.widget {
+                window_builder = window_builder
+                    .with_decorations(false)
+                    .with_window_level(winit::window::WindowLevel::AlwaysOnTop);
+            }
+            let window = window_builder
+                .build(&event_loop)
+                .map_err(error::Error::Window)?;
+            if config.fullscreen {
+                window.set_cursor_visible(false);
+            }
+            if config.widget {
+                if let Err(err) = window.set_cursor_hittest(false) {
+                    log::warn!("failed to make window click-through: {}", err);
+                }
+            }
+            windows.push(Window::Winit(window));
+        }
+    }
+
+    let mut screens = std::collections::HashMap::new();
+    for window in windows {
+        let window_id = window.id();
+        let mut renderer = pollster::block_on(Renderer::new(Some(window), &config))?;
+        let transparent = config.transparent || config.widget;
+        let picture_store = if !transparent && !renderer.has_shader() {
+            picture_store::PictureStore::new(&config)?
+        } else {
+            None
+        };
+        if !transparent && !renderer.has_shader() && picture_store.is_none() {
+            log::warn!("show procedural background instead of pictures");
+            pollster::block_on(renderer.set_procedural_background());
+        }
+        screens.insert(
+            window_id,
+            Screen {
+                renderer,
+                picture_store,
+                picture_instant: std::time::Instant::now(),
+                picture_duration: None,
+                picture_wanted: false,
+                picture_name: None,
+                picture_hook: config.on_picture.clone(),
+                touch_start: None,
+            },
+        );
+    }
+
+    let mut renderer_config = config.clone();
+    let mut alarm_clock = alarm::AlarmClock::new(
+        config.alarms,
+        config.timezone,
+        std::time::Duration::from_secs(config.alarm_duration),
+    );
+    let alarm_sound = config.alarm_sound;
+    let mut chime = config.chime.map(|sound| {
+        (
+            alarm::Chime::new(config.timezone, config.quiet_hours),
+            sound,
+        )
+    });
+    let volume = config.volume;
+    let mut hour_hook = config
+        .on_hour
+        .map(|command| (alarm::Chime::new(config.timezone, None), command));
+    let alarm_hook = config.on_alarm;
+    let screenshot_dir = config.screenshot_dir;
+
+    let weather = match (config.weather_api_key, config.weather_location) {
+        (Some(api_key), Some(location)) => Some(weather::spawn(
+            api_key,
+            location,
+            config.weather_units,
+            std::time::Duration::from_secs(config.weather_interval),
+        )),
+        _ => None,
+    };
+
+    let calendar = (!config.calendars.is_empty()).then(|| {
+        calendar::spawn(
+            config.calendars,
+            std::time::Duration::from_secs(config.calendar_interval),
+        )
+    });
+    let mut calendar_events = vec![];
+
+    let ntp = config.ntp_server.map(|ntp_server| {
+        ntp::spawn(
+            ntp_server,
+            std::time::Duration::from_secs(config.ntp_interval),
+        )
+    });
+    let ntp_threshold = config.ntp_threshold;
+
+    let stats = config
+        .stats
+        .then(|| stats::spawn(std::time::Duration::from_secs(config.stats_interval)));
+    // kept until exit, event loop never returns
+    let _inhibitor = config
+        .inhibit_sleep
+        .then(|| match inhibit::Inhibitor::new() {
+            Ok(inhibitor) => Some(inhibitor),
+            Err(err) => {
+                log::error!("failed to inhibit sleep: {}", err);
+                None
+            }
+        })
+        .flatten();
+
+    let night_mode = config.night.map(|schedule| {
+        night::NightMode::new(
+            schedule,
+            config.timezone,
+            config.night_brightness,
+            config.night_red_shift,
+        )
+    });
+
+    let backlight = config.backlight.as_ref().map(|target| {
+        backlight::spawn(
+            backlight::Backlight::new(target),
+            backlight::Schedule {
+                day: config.backlight_day,
+                night: config.backlight_night,
+                night_mode: night_mode.clone(),
+                ambient_light: config
+                    .ambient_light
+                    .as_ref()
+                    .map(|path| (path.into(), config.ambient_light_max)),
+            },
+            std::time::Duration::from_secs(config.backlight_interval),
+        )
+    });
+
+    let sun = config
+        .latitude
+        .zip(config.longitude)
+        .filter(|_| config.sun_tint)
+        .map(|(latitude, longitude)| sun::Sun::new(latitude, longitude));
+
+    let moon = config.moon;
+    let mut moon_phase = None;
+
+    let burn_in_shift = config.burn_in_shift;
+    let burn_in_period = config.burn_in_period.max(1);
+    let burn_in_picture = config.burn_in_picture;
+
+    let battery = config
+        .battery
+        .then(|| battery::spawn(std::time::Duration::from_secs(config.battery_interval)));
+
+    let animation_interval = std::time::Duration::from_millis(ANIMATION_INTERVAL);
+    let mut stopwatch = stopwatch::Stopwatch::new();
+    let mut stopwatch_mode = false;
+    // face switched by tap, kept over reloads of config
+    let mut chosen_face = None;
+    let keymap = config.keys;
+    let mut paused = false;
+
+    let status = std::sync::Arc::new(std::sync::Mutex::new(control::Status::default()));
+    // receivers of remote control commands
+    let mut controls = vec![];
+    if let Some(http_port) = config.http_port {
+        controls.push(http::spawn(http_port, status.clone()));
+    }
+    if let Some(mqtt_host) = config.mqtt_host {
+        let broker = mqtt::Broker {
+            host: mqtt_host,
+            port: config.mqtt_port,
+            credentials: config.mqtt_username.zip(config.mqtt_password),
+            topic: config.mqtt_topic,
+        };
+        controls.push(mqtt::spawn(broker, status.clone(), update_interval));
+    }
+    if config.dbus {
+        controls.push(dbus::spawn(status.clone()));
+    }
+    if config.control_socket {
+        controls.push(socket::spawn(status.clone())?);
+    }
+    let mut message = None;
+
+    for screen in screens.values_mut() {
+        screen.next_picture();
+    }
+
+    let mut notifier = systemd::Notifier::from_env();
+    if let Some(notifier) = &notifier {
+        notifier.ready();
+    }
 
     log::debug!("start event loop");
+    use winit::event::ElementState;
     use winit::event::Event;
+    use winit::event::KeyboardInput;
     use winit::event::StartCause;
+    use winit::event::Touch;
+    use winit::event::TouchPhase;
     use winit::event::WindowEvent;
     event_loop.run(move |event, _, control_flow| match event {
         Event::NewEvents(StartCause::Init) => {
-            control_flow.set_wait_timeout(update_interval);
+            control_flow.set_wait_until(next_wake(tick_interval, notifier.as_ref()));
         }
         Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
-            renderer.request_redraw();
-            control_flow.set_wait_timeout(update_interval);
+            use control::Command;
+
+            // a hung event loop stops pinging, so systemd restarts the process
+            if let Some(notifier) = &mut notifier {
+                notifier.ping();
+            }
+            let mut ring = alarm_clock.update();
+            for command in controls.iter().flat_map(|control| control.try_iter()) {
+                match command {
+                    Command::NextPicture => {
+                        for screen in screens.values_mut() {
+                            screen.next_picture();
+                        }
+                    }
+                    Command::PreviousPicture => {
+                        for screen in screens.values_mut() {
+                            screen.previous_picture();
+                        }
+                    }
+                    Command::SetMessage(new_message) => {
+                        message = new_message;
+                        for screen in screens.values_mut() {
+                            screen.renderer.set_message(message.clone());
+                        }
+                    }
+                    Command::TriggerAlarm => {
+                        alarm_clock.trigger();
+                        ring = true;
+                    }
+                    Command::DismissAlarm => {
+                        alarm_clock.dismiss();
+                    }
+                    Command::SetBrightness(percent) => match &backlight {
+                        Some(backlight) => {
+                            let _ = backlight.send(percent);
+                        }
+                        None => log::warn!("backlight is not configured"),
+                    },
+                    Command::SetPaused(new_paused) => {
+                        paused = new_paused;
+                    }
+                    Command::SetProfile(Some(name))
+                        if !base_config
+                            .profiles
+                            .iter()
+                            .any(|profile| profile.name == name) =>
+                    {
+                        log::warn!("unknown profile: {}", name);
+                    }
+                    Command::SetProfile(name) => {
+                        chosen_profile = name;
+                    }
+                }
+            }
+            if ring {
+                if let Some(alarm_sound) = &alarm_sound {
+                    audio::play(alarm_sound, volume);
+                }
+                if let Some(alarm_hook) = &alarm_hook {
+                    hook::run(alarm_hook, &[]);
+                }
+            }
+            if let Some((chime, sound)) = &mut chime {
+                if chime.update() {
+                    audio::play(sound, volume);
+                }
+            }
+            if let Some((hour, command)) = &mut hour_hook {
+                if hour.update() {
+                    hook::run(command, &[]);
+                }
+            }
+            metrics::set_picture_store_bytes(
+                screens
+                    .values()
+                    .filter_map(|screen| screen.picture_store.as_ref())
+                    .map(picture_store::PictureStore::memory)
+                    .sum(),
+            );
+            *status.lock().unwrap() = control::Status {
+                pictures: screens
+                    .values()
+                    .filter_map(|screen| screen.picture_name.clone())
+                    .collect(),
+                message: message.clone(),
+                alarm_ringing: alarm_clock.is_ringing(),
+                paused,
+                profile: profile.map(|profile| base_config.profiles[profile].name.clone()),
+            };
+            for screen in screens.values() {
+                screen.renderer.request_redraw();
+            }
+            let animating = screens
+                .values()
+                .any(|screen| screen.renderer.is_animating());
+            if (stopwatch_mode && stopwatch.is_running()) || animating {
+                control_flow.set_wait_timeout(animation_interval);
+            } else {
+                control_flow.set_wait_until(next_wake(tick_interval, notifier.as_ref()));
+            }
         }
-        Event::RedrawRequested(window_id) if renderer.match_window(window_id) => {
-            if picture_interval < picture_interval_instance.elapsed() {
-                renderer.set_picture(pictures.choose(&mut rng).unwrap());
-                picture_interval_instance = std::time::Instant::now();
+        Event::RedrawRequested(window_id) | Event::UserEvent(window_id)
+            if screens.contains_key(&window_id) =>
+        {
+            let mut reload = false;
+            if config_watcher
+                .as_ref()
+                .is_some_and(|(_, changes)| changes.try_iter().last().is_some())
+            {
+                // validated as on startup, so a broken config keeps the running one
+                match reload_args.clone().into_config() {
+                    Ok(config) => {
+                        log::debug!("reload config");
+                        base_config = config;
+                        reload = true;
+                    }
+                    Err(err) => log::error!("failed to reload config: {}", err),
+                }
+            }
+            let active_profile = profile::active(&base_config, chosen_profile.as_deref());
+            if active_profile != profile {
+                log::debug!("switch profile");
+                profile = active_profile;
+                reload = true;
+            }
+            if reload {
+                theme = theme::active(&base_config);
+                let mut config = theme::apply(&profile::apply(&base_config, profile), theme);
+                config.face = chosen_face.unwrap_or(config.face);
+                let pictures_changed = config.picture_path != renderer_config.picture_path;
+                tick_interval = self::tick_interval(&config);
+                picture_interval = std::time::Duration::from_secs(config.picture_interval);
+                renderer_config = config;
+                for screen in screens.values_mut() {
+                    screen.reload(&renderer_config, pictures_changed);
+                }
+            }
+
+            let active_theme = theme::active(&base_config);
+            if active_theme != theme {
+                log::debug!("switch theme");
+                theme = active_theme;
+                let mut config = theme::apply(&profile::apply(&base_config, profile), theme);
+                config.face = chosen_face.unwrap_or(config.face);
+                let pictures_changed = config.picture_path != renderer_config.picture_path;
+                renderer_config = config;
+                for screen in screens.values_mut() {
+                    screen.set_theme(&renderer_config, pictures_changed);
+                }
+            }
+
+            // updates from workers are received once, so hand them to every screen
+            if let Some(weather) = weather
+                .as_ref()
+                .and_then(|weather| weather.try_iter().last())
+            {
+                for screen in screens.values_mut() {
+                    screen.renderer.set_weather(&weather);
+                    if let Some(picture_store) = &screen.picture_store {
+                        picture_store.set_weather(weather.condition);
+                    }
+                }
+            }
+            if let Some(events) = calendar
+                .as_ref()
+                .and_then(|calendar| calendar.try_iter().last())
+            {
+                calendar_events = events;
+            }
+            if let Some(offset) = ntp.as_ref().and_then(|ntp| ntp.try_iter().last()) {
+                for screen in screens.values_mut() {
+                    screen
+                        .renderer
+                        .set_drift((ntp_threshold < offset.abs()).then_some(offset));
+                }
+            }
+            if let Some(stats) = stats.as_ref().and_then(|stats| stats.try_iter().last()) {
+                for screen in screens.values_mut() {
+                    screen.renderer.set_stats(Some(stats.clone()));
+                }
+            }
+            if let Some(battery) = battery
+                .as_ref()
+                .and_then(|battery| battery.try_iter().last())
+            {
+                for screen in screens.values_mut() {
+                    screen.renderer.set_battery(Some(battery.clone()));
+                }
+            }
+
+            if moon {
+                let phase = moon::phase(chrono::Utc::now());
+                if moon_phase.is_none_or(|last: f64| MOON_PHASE_STEP < (phase - last).abs()) {
+                    moon_phase = Some(phase);
+                    for screen in screens.values_mut() {
+                        screen.renderer.set_moon(phase);
+                    }
+                }
+            }
+
+            let screen = screens.get_mut(&window_id).unwrap();
+            let picture_duration = screen.picture_duration.unwrap_or(picture_interval);
+            let picture_elapsed = screen.picture_instant.elapsed();
+            if !paused && picture_duration.saturating_sub(PICTURE_PREFETCH) < picture_elapsed {
+                screen.prefetch_picture();
+            }
+            if screen.picture_wanted || (!paused && picture_duration < picture_elapsed) {
+                if let Some(picture) = screen
+                    .picture_store
+                    .as_mut()
+                    .and_then(|picture_store| picture_store.try_next_picture())
+                {
+                    screen.show_picture(picture);
+                }
+            }
+            let now = chrono::Utc::now();
+            screen
+                .renderer
+                .set_event(calendar_events.iter().find(|event| now < event.start));
+            let flash = alarm_clock.is_ringing() && chrono::Utc::now().timestamp() % 2 == 0;
+            screen.renderer.set_overlay(if flash {
+                [1.0, 1.0, 1.0, 0.5]
+            } else {
+                [0.0; 4]
+            });
+            screen
+                .renderer
+                .set_stopwatch(stopwatch_mode.then(|| stopwatch.elapsed()));
+            if night_mode.is_some() || sun.is_some() {
+                let tint = night_mode
+                    .as_ref()
+                    .map_or([1.0; 3], |night_mode| night_mode.tint());
+                let warmth = sun.map_or([1.0; 3], |sun| sun.tint(chrono::Utc::now()));
+                screen.renderer.set_tint(tint, warmth);
+            }
+            if 0.0 < burn_in_shift {
+                let shift = burn_in_offset(burn_in_shift, burn_in_period);
+                screen.renderer.set_shift(shift, burn_in_picture);
+            }
+            // wgpu panics on fatal errors such as device loss, which a new device recovers from
+            let drawn =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| screen.renderer.draw()));
+            if drawn.is_err() || screen.renderer.is_lost() {
+                if let Err(err) = screen.recover(&renderer_config) {
+                    eprintln!("error: {}", err);
+                    std::process::exit(1);
+                }
+                // moon is drawn only on change of phase
+                moon_phase = None;
+                screen.renderer.request_redraw();
+            }
+            if screen.renderer.is_animating() {
+                control_flow.set_wait_timeout(animation_interval);
             }
-            renderer.draw();
         }
-        Event::WindowEvent { window_id, event } if renderer.match_window(window_id) => {
+        Event::WindowEvent { window_id, event } if screens.contains_key(&window_id) => {
             match event {
                 WindowEvent::Resized(new_inner_size) => {
-                    renderer.resize(new_inner_size);
+                    screens
+                        .get_mut(&window_id)
+                        .unwrap()
+                        .renderer
+                        .resize(new_inner_size);
                 }
                 WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                    renderer.resize(*new_inner_size);
+                    screens
+                        .get_mut(&window_id)
+                        .unwrap()
+                        .renderer
+                        .resize(*new_inner_size);
+                }
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(keycode),
+                            ..
+                        },
+                    ..
+                } => {
+                    use keymap::Action;
+
+                    alarm_clock.dismiss();
+                    match keymap.action(keycode) {
+                        Some(Action::Stopwatch) => {
+                            stopwatch_mode = !stopwatch_mode;
+                        }
+                        Some(Action::StopwatchStart) if stopwatch_mode => {
+                            stopwatch.toggle();
+                        }
+                        Some(Action::StopwatchReset) if stopwatch_mode => {
+                            stopwatch.reset();
+                        }
+                        Some(Action::NextPicture) => {
+                            screens.get_mut(&window_id).unwrap().next_picture();
+                            control_flow.set_wait_timeout(animation_interval);
+                        }
+                        Some(Action::NextProfile) => {
+                            chosen_profile = profile::next(&base_config, profile);
+                        }
+                        Some(Action::Fullscreen) => {
+                            screens[&window_id].renderer.toggle_fullscreen();
+                        }
+                        Some(Action::Pause) => {
+                            paused = !paused;
+                            log::debug!("pause picture rotation: {}", paused);
+                        }
+                        Some(Action::Screenshot) => {
+                            let path = std::path::Path::new(&screenshot_dir).join(
+                                chrono::Local::now()
+                                    .format("clock-app-%Y%m%d-%H%M%S.png")
+                                    .to_string(),
+                            );
+                            let image = screens.get_mut(&window_id).unwrap().renderer.capture();
+                            match image.save_with_format(&path, image::ImageFormat::Png) {
+                                Ok(()) => log::debug!("save screenshot {}", path.display()),
+                                Err(err) => log::error!("failed to save screenshot: {}", err),
+                            }
+                        }
+                        Some(Action::Quit) => {
+                            control_flow.set_exit();
+                        }
+                        _ => {}
+                    }
+                    for screen in screens.values() {
+                        screen.renderer.request_redraw();
+                    }
+                    if stopwatch_mode && stopwatch.is_running() {
+                        control_flow.set_wait_timeout(animation_interval);
+                    }
+                }
+                WindowEvent::MouseInput { .. } => {
+                    alarm_clock.dismiss();
+                }
+                WindowEvent::Touch(Touch {
+                    phase,
+                    location,
+                    id,
+                    ..
+                }) => {
+                    let screen = screens.get_mut(&window_id).unwrap();
+                    match phase {
+                        TouchPhase::Started => {
+                            // other fingers of multi-touch are ignored
+                            if screen.touch_start.is_none() {
+                                screen.touch_start = Some((id, location));
+                            }
+                        }
+                        TouchPhase::Ended => {
+                            let Some((start_id, start)) = screen.touch_start else {
+                                return;
+                            };
+                            if start_id != id {
+                                return;
+                            }
+                            screen.touch_start = None;
+                            alarm_clock.dismiss();
+
+                            let (dx, dy) = (location.x - start.x, location.y - start.y);
+                            if SWIPE_DISTANCE <= dx.abs() && dy.abs() < dx.abs() {
+                                if dx < 0.0 {
+                                    screen.next_picture();
+                                } else {
+                                    screen.previous_picture();
+                                }
+                            } else if dx.abs() < SWIPE_DISTANCE && dy.abs() < SWIPE_DISTANCE {
+                                let face = renderer_config.face.next();
+                                log::debug!("switch face");
+                                chosen_face = Some(face);
+                                renderer_config.face = face;
+                                for screen in screens.values_mut() {
+                                    screen.reload(&renderer_config, false);
+                                }
+                            }
+                            for screen in screens.values() {
+                                screen.renderer.request_redraw();
+                            }
+                            control_flow.set_wait_timeout(animation_interval);
+                        }
+                        TouchPhase::Cancelled => {
+                            if screen
+                                .touch_start
+                                .is_some_and(|(start_id, _)| start_id == id)
+                            {
+                                screen.touch_start = None;
+                            }
+                        }
+                        TouchPhase::Moved => {}
+                    }
                 }
                 WindowEvent::CloseRequested => {
-                    control_flow.set_exit();
+                    screens.remove(&window_id);
+                    if screens.is_empty() {
+                        control_flow.set_exit();
+                    }
                 }
                 _ => {}
             }
         }
+        Event::LoopDestroyed => {
+            if let Some(notifier) = &notifier {
+                notifier.stopping();
+            }
+        }
         _ => {}
     });
 }
 
+/// Draws the first picture and texts into an image file, for e-ink displays and snapshots
+#[cfg(not(target_arch = "wasm32"))]
+fn render_once(mut config: config::Config, path: &str) -> Result<(), error::Error> {
+    log::debug!("render once into {}", path);
+    // the picture must be shown fully without transition
+    config.picture_transition = 0;
+
+    let mut renderer = pollster::block_on(Renderer::new(None, &config))?;
+    let transparent = config.transparent || config.widget;
+    if !transparent && !renderer.has_shader() {
+        match picture_store::PictureStore::new(&config)? {
+            Some(mut picture_store) => match picture_store.wait_next_picture(PICTURE_WAIT) {
+                Some(picture) => renderer.set_picture(picture),
+                None => log::warn!("no picture is loaded in time, render without it"),
+            },
+            None => pollster::block_on(renderer.set_procedural_background()),
+        }
+    }
+    if config.moon {
+        renderer.set_moon(moon::phase(chrono::Utc::now()));
+    }
+
+    renderer
+        .capture()
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|err| error::Error::Image(path.to_string(), err))
+}
+
+/// Output of frames drawn offscreen, without windows
+#[cfg(not(target_arch = "wasm32"))]
+enum Headless {
+    Eink(eink::Display),
+    Drm(drm::Output),
+}
+
+/// Redraws frames into e-ink or DRM/KMS output, instead of the event loop
+#[cfg(not(target_arch = "wasm32"))]
+fn run_headless(mut base_config: config::Config) -> Result<(), error::Error> {
+    let mut output = match (base_config.eink.take(), base_config.drm.take()) {
+        (Some(path), _) => {
+            log::debug!("start e-ink output {}", path);
+            let display = eink::Display::open(&path, base_config.eink_levels)
+                .map_err(|err| error::Error::Eink(path.clone(), err))?;
+            // animations cannot be shown, and seconds would be stale between updates
+            base_config.picture_transition = 0;
+            base_config.ken_burns_zoom = 0.0;
+            if base_config.format.is_none() {
+                base_config.format = Some(base_config.time_format.short_pattern().to_string());
+            }
+            Headless::Eink(display)
+        }
+        (None, Some(path)) => {
+            log::debug!("start drm output {}", path);
+            let output =
+                drm::Output::open(&path).map_err(|err| error::Error::Drm(path.clone(), err))?;
+            let (width, height) = output.size();
+            (base_config.width, base_config.height) = (Some(width), Some(height));
+            Headless::Drm(output)
+        }
+        (None, None) => unreachable!(),
+    };
+    let mut profile = profile::active(&base_config, base_config.profile.as_deref());
+    let mut theme = theme::active(&base_config);
+    let mut config = theme::apply(&profile::apply(&base_config, profile), theme);
+
+    let mut renderer = pollster::block_on(Renderer::new(None, &config))?;
+    let mut picture_store = if !renderer.has_shader() {
+        picture_store::PictureStore::new(&config)?
+    } else {
+        None
+    };
+    if !renderer.has_shader() && picture_store.is_none() {
+        pollster::block_on(renderer.set_procedural_background());
+    }
+    let mut picture_interval = std::time::Duration::from_secs(config.picture_interval);
+    let mut picture_instant = None::<std::time::Instant>;
+    let mut picture_duration = None;
+    let mut notifier = systemd::Notifier::from_env();
+    if let Some(notifier) = &notifier {
+        notifier.ready();
+    }
+
+    loop {
+        let active_profile = profile::active(&base_config, base_config.profile.as_deref());
+        let active_theme = theme::active(&base_config);
+        if (active_profile, active_theme) != (profile, theme) {
+            log::debug!("switch profile or theme");
+            let new_config =
+                theme::apply(&profile::apply(&base_config, active_profile), active_theme);
+            if active_profile != profile {
+                renderer.reload(&new_config);
+            } else {
+                renderer.set_theme(&new_config);
+            }
+            let pictures_changed = new_config.picture_path != config.picture_path;
+            profile = active_profile;
+            theme = active_theme;
+            config = new_config;
+            picture_interval = std::time::Duration::from_secs(config.picture_interval);
+            if !renderer.has_shader() && pictures_changed {
+                match picture_store::PictureStore::new(&config) {
+                    Ok(new_picture_store) => {
+                        picture_store = new_picture_store;
+                        picture_instant = None;
+                        if picture_store.is_some() {
+                            renderer.clear_procedural_background();
+                        } else if !renderer.procedural {
+                            pollster::block_on(renderer.set_procedural_background());
+                        }
+                    }
+                    Err(err) => log::error!("failed to switch theme pictures: {}", err),
+                }
+            }
+        }
+        if let Some(picture_store) = &mut picture_store {
+            let duration = picture_duration.unwrap_or(picture_interval);
+            if picture_instant.is_none_or(|instant| duration < instant.elapsed()) {
+                if let Some(picture) = picture_store.wait_next_picture(PICTURE_WAIT) {
+                    picture_duration = picture.duration;
+                    renderer.set_picture(picture);
+                    picture_instant = Some(std::time::Instant::now());
+                }
+            }
+        }
+        if config.moon {
+            renderer.set_moon(moon::phase(chrono::Utc::now()));
+        }
+        let frame = renderer.capture();
+        match &mut output {
+            Headless::Eink(display) => {
+                if let Err(err) = display.show(&frame) {
+                    log::error!("failed to write e-ink output: {}", err);
+                }
+            }
+            Headless::Drm(output) => output.show(&frame),
+        }
+
+        if let Some(notifier) = &mut notifier {
+            notifier.ping();
+        }
+        let wake = match &output {
+            Headless::Eink(_) => next_tick(std::time::Duration::from_secs(config.eink_interval)),
+            Headless::Drm(_) if renderer.is_animating() => {
+                std::time::Instant::now() + std::time::Duration::from_millis(ANIMATION_INTERVAL)
+            }
+            Headless::Drm(_) => next_tick(tick_interval(&config)),
+        };
+        std::thread::sleep(wake.saturating_duration_since(std::time::Instant::now()));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open_log_file(config: &config::Config) {
+    if let Some(log_file) = &config.log_file {
+        logger::open_file(
+            log_file,
+            config.log_max_size * 1024,
+            std::time::Duration::from_secs(config.log_max_age * 3600),
+            config.log_keep,
+        );
+    }
+}
+
+/// Interval of ticks updating texts, every half second for blinking colon
+fn tick_interval(config: &config::Config) -> std::time::Duration {
+    let update_interval = std::time::Duration::from_millis(config.update_interval);
+    if config.blink_colon {
+        update_interval.min(std::time::Duration::from_millis(500))
+    } else {
+        update_interval
+    }
+}
+
+/// Next multiple of interval on wall clock, so that updates land on second boundaries
+#[cfg(not(target_arch = "wasm32"))]
+fn next_tick(interval: std::time::Duration) -> std::time::Instant {
+    std::time::Instant::now() + until_next_tick(interval)
+}
+
+fn until_next_tick(interval: std::time::Duration) -> std::time::Duration {
+    let interval = (interval.as_millis() as i64).max(1);
+    let millis = chrono::Utc::now().timestamp_millis();
+    let wait = interval - millis.rem_euclid(interval);
+    std::time::Duration::from_millis(wait as u64)
+}
+
+/// Next tick, or the watchdog ping if it is due earlier
+#[cfg(not(target_arch = "wasm32"))]
+fn next_wake(
+    interval: std::time::Duration,
+    notifier: Option<&systemd::Notifier>,
+) -> std::time::Instant {
+    let tick = next_tick(interval);
+    notifier
+        .and_then(systemd::Notifier::next_ping)
+        .map_or(tick, |ping| ping.min(tick))
+}
+
+/// Offset against burn-in, hopping within the amplitude once per period
+#[cfg(not(target_arch = "wasm32"))]
+fn burn_in_offset(amplitude: f32, period: u64) -> (f32, f32) {
+    let step = chrono::Utc::now().timestamp() as u64 / period;
+    // golden angle and square root radius spread successive offsets evenly over the disk
+    let angle = step as f32 * 2.399963;
+    let radius = amplitude * ((step % 16) as f32 / 15.0).sqrt();
+    (
+        (angle.cos() * radius).round(),
+        (angle.sin() * radius).round(),
+    )
+}
+
+/// Monitors to open windows on, `None` leaves placement to the window manager
+#[cfg(not(target_arch = "wasm32"))]
+fn select_monitors(
+    event_loop: &winit::event_loop::EventLoop<winit::window::WindowId>,
+    config: &config::Config,
+) -> Result<Vec<Option<winit::monitor::MonitorHandle>>, error::Error> {
+    if config.all_monitors {
+        let monitors = event_loop
+            .available_monitors()
+            .map(Some)
+            .collect::<Vec<_>>();
+        if !monitors.is_empty() {
+            return Ok(monitors);
+        }
+        log::warn!("no monitor found");
+    }
+    match &config.monitor {
+        Some(selector) => {
+            let monitor = event_loop
+                .available_monitors()
+                .enumerate()
+                .find(|(index, monitor)| {
+                    selector == &index.to_string() || monitor.name().as_ref() == Some(selector)
+                })
+                .map(|(_, monitor)| monitor)
+                .ok_or_else(|| error::Error::MonitorNotFound(selector.clone()))?;
+            Ok(vec![Some(monitor)])
+        }
+        None => Ok(vec![None]),
+    }
+}
+
+/// Makes window behave like wallpaper below other windows, which X11 window managers respect
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn with_desktop_type(
+    window_builder: winit::window::WindowBuilder,
+    event_loop: &winit::event_loop::EventLoop<winit::window::WindowId>,
+    monitor: Option<&winit::monitor::MonitorHandle>,
+) -> winit::window::WindowBuilder {
+    use winit::platform::x11::EventLoopWindowTargetExtX11;
+    use winit::platform::x11::WindowBuilderExtX11;
+
+    if !event_loop.is_x11() {
+        log::warn!("desktop window is supported only on X11");
+        return window_builder;
+    }
+    let monitor = monitor.cloned().or_else(|| event_loop.primary_monitor());
+    let window_builder = match monitor {
+        Some(monitor) => window_builder
+            .with_position(monitor.position())
+            .with_inner_size(monitor.size()),
+        None => window_builder,
+    };
+    window_builder
+        .with_decorations(false)
+        .with_x11_window_type(vec![winit::platform::x11::XWindowType::Desktop])
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_arch = "wasm32"
+)))]
+fn with_desktop_type(
+    window_builder: winit::window::WindowBuilder,
+    _event_loop: &winit::event_loop::EventLoop<winit::window::WindowId>,
+    _monitor: Option<&winit::monitor::MonitorHandle>,
+) -> winit::window::WindowBuilder {
+    log::warn!("desktop window is supported only on X11");
+    window_builder
+}
+
+/// Target presenting rendered frames
+enum Window {
+    Winit(winit::window::Window),
+    #[cfg(feature = "layer-shell")]
+    Layer(Box<layer_shell::LayerWindow>),
+}
+
+impl Window {
+    fn id(&self) -> winit::window::WindowId {
+        match self {
+            Self::Winit(window) => window.id(),
+            #[cfg(feature = "layer-shell")]
+            Self::Layer(layer) => layer.id(),
+        }
+    }
+
+    fn inner_size(&self) -> winit::dpi::PhysicalSize<u32> {
+        match self {
+            Self::Winit(window) => window.inner_size(),
+            #[cfg(feature = "layer-shell")]
+            Self::Layer(layer) => layer.inner_size(),
+        }
+    }
+
+    fn request_redraw(&self) {
+        match self {
+            Self::Winit(window) => window.request_redraw(),
+            #[cfg(feature = "layer-shell")]
+            Self::Layer(layer) => layer.request_redraw(),
+        }
+    }
+
+    fn toggle_fullscreen(&self) {
+        match self {
+            Self::Winit(window) => {
+                let fullscreen = window.fullscreen().is_none();
+                window.set_fullscreen(
+                    fullscreen.then_some(winit::window::Fullscreen::Borderless(None)),
+                );
+                window.set_cursor_visible(!fullscreen);
+            }
+            #[cfg(feature = "layer-shell")]
+            Self::Layer(_) => log::warn!("layer surface cannot toggle fullscreen"),
+        }
+    }
+
+    /// New size of window whose resize is not notified by winit
+    fn poll_resize(&mut self) -> Option<winit::dpi::PhysicalSize<u32>> {
+        match self {
+            Self::Winit(_) => None,
+            #[cfg(feature = "layer-shell")]
+            Self::Layer(layer) => layer.poll_resize(),
+        }
+    }
+}
+
+/// Window with its own renderer and picture rotation
+#[cfg(not(target_arch = "wasm32"))]
+struct Screen {
+    renderer: Renderer,
+    picture_store: Option<picture_store::PictureStore>,
+    picture_instant: std::time::Instant,
+    /// Display duration of the current picture overriding picture interval
+    picture_duration: Option<std::time::Duration>,
+    /// Next picture is shown as soon as it is decoded, having been skipped to
+    picture_wanted: bool,
+    picture_name: Option<String>,
+    picture_hook: Option<String>,
+    /// Touch id and position where the ongoing touch started
+    touch_start: Option<(u64, winit::dpi::PhysicalPosition<f64>)>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Screen {
+    /// Switches to next picture without waiting for picture interval, or as soon as it is
+    /// decoded
+    fn next_picture(&mut self) {
+        let Some(picture_store) = &mut self.picture_store else {
+            return;
+        };
+        match picture_store.try_next_picture() {
+            Some(picture) => self.show_picture(picture),
+            None => self.picture_wanted = true,
+        }
+    }
+
+    /// Uploads the next picture ahead of the switch, once it is decoded
+    fn prefetch_picture(&mut self) {
+        if let Some(picture) = self
+            .picture_store
+            .as_mut()
+            .and_then(|picture_store| picture_store.prefetch())
+        {
+            self.renderer.prefetch_picture(picture);
+        }
+    }
+
+    /// Goes back a picture, shown as soon as it is decoded again if it is not kept
+    fn previous_picture(&mut self) {
+        let Some(picture_store) = &mut self.picture_store else {
+            return;
+        };
+        match picture_store.previous_picture() {
+            Some(picture) => self.show_picture(picture),
+            None => self.picture_wanted |= picture_store.is_recalling(),
+        }
+    }
+
+    /// Recreates renderer after GPU device reset, showing the current picture again
+    fn recover(&mut self, config: &config::Config) -> Result<(), error::Error> {
+        log::warn!("recreate renderer");
+        self.renderer.recreate(config)?;
+        match &mut self.picture_store {
+            Some(picture_store) => match picture_store.current_picture() {
+                Some(picture) => self.renderer.set_picture(picture),
+                None => self.picture_wanted |= picture_store.is_recalling(),
+            },
+            None if !self.renderer.transparent && !self.renderer.has_shader() => {
+                pollster::block_on(self.renderer.set_procedural_background());
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Switches to pictures, text color and greeting of the theme applied to config
+    fn set_theme(&mut self, config: &config::Config, pictures_changed: bool) {
+        self.renderer.set_theme(config);
+        if pictures_changed {
+            self.reload_pictures(config);
+        }
+    }
+
+    /// Applies reloaded config, picking pictures again only if their path changed
+    fn reload(&mut self, config: &config::Config, pictures_changed: bool) {
+        self.renderer.reload(config);
+        self.picture_hook = config.on_picture.clone();
+        if pictures_changed {
+            self.reload_pictures(config);
+        }
+    }
+
+    /// Builds pictures of the path, also when there were none before
+    fn reload_pictures(&mut self, config: &config::Config) {
+        if self.renderer.transparent || self.renderer.has_shader() {
+            return;
+        }
+        match picture_store::PictureStore::new(config) {
+            Ok(Some(picture_store)) => {
+                self.renderer.clear_procedural_background();
+                self.picture_store = Some(picture_store);
+                self.next_picture();
+            }
+            Ok(None) => {
+                self.picture_store = None;
+                if !self.renderer.procedural {
+                    log::warn!("show procedural background instead of pictures");
+                    pollster::block_on(self.renderer.set_procedural_background());
+                }
+            }
+            Err(err) => log::error!("failed to switch pictures: {}", err),
+        }
+    }
+
+    fn show_picture(&mut self, picture: picture::Picture) {
+        if let Some(picture_hook) = &self.picture_hook {
+            hook::run(picture_hook, &[("CLOCK_APP_PICTURE", &picture.name)]);
+        }
+        self.picture_name = Some(picture.name.clone());
+        self.picture_duration = picture.duration;
+        self.renderer.set_picture(picture);
+        self.picture_instant = std::time::Instant::now();
+        self.picture_wanted = false;
+    }
+}
+
+/// Draws into window, or into offscreen texture if window is none
 struct Renderer {
-    window: winit::window::Window,
-    surface: wgpu::Surface,
+    window: Option<Window>,
+    surface: Option<wgpu::Surface>,
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
     picture_pipeline: crate::picture::PicturePipeline,
+    overlay_pipeline: crate::overlay::OverlayPipeline,
+    weather_icon_pipeline: crate::icon::IconPipeline,
+    moon_icon_pipeline: crate::icon::IconPipeline,
+    shader_pipeline: Option<crate::shader::ShaderPipeline>,
+    /// Whether shader pipeline is the gradient standing in for missing pictures
+    procedural: bool,
     text_pipeline: crate::text::TextPipeline,
+    blur_pipeline: Option<crate::blur::BlurPipeline>,
+    blur_region: crate::blur::BlurRegion,
+    scrim_pipeline: Option<crate::scrim::ScrimPipeline>,
+    transparent: bool,
+    /// Whether output differs from the last drawn one, other than texts
+    dirty: bool,
+    /// Whether the next redraw is requested by application, not by system to restore window
+    redraw_requested: std::cell::Cell<bool>,
+    /// Set by GPU errors after which device cannot be used anymore
+    lost: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// Multiplier of colors placing white at HDR white level, 1.0 on SDR surface
+    brightness: f32,
+    sample_count: u32,
+    /// Target of drawing resolved into the output view, if multisampled
+    multisampled: Option<wgpu::TextureView>,
+    overlay: [f32; 4],
+    tint: ([f32; 3], [f32; 3]),
+    shift: (f32, f32),
 }
 
 impl Renderer {
-    async fn new(window: winit::window::Window, picture_width: u32, picture_height: u32) -> Self {
+    async fn new(
+        window: Option<Window>,
+        app_config: &config::Config,
+    ) -> Result<Self, error::Error> {
         log::debug!("create renderering resource");
         log::debug!("create instance");
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let instance = gpu::instance(app_config.gpu_backend);
         log::debug!("create surface");
-        let surface = unsafe { instance.create_surface(&window) }.unwrap();
-        log::debug!("create adapter");
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::LowPower,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+        let surface = window
+            .as_ref()
+            .map(|window| unsafe {
+                match window {
+                    Window::Winit(window) => instance.create_surface(window),
+                    #[cfg(feature = "layer-shell")]
+                    Window::Layer(layer) => instance.create_surface(layer.as_ref()),
+                }
             })
-            .await
-            .unwrap();
+            .transpose()
+            .map_err(error::Error::Surface)?;
+        log::debug!("create adapter");
+        let adapter = gpu::select_adapter(
+            &instance,
+            app_config.gpu_backend,
+            app_config.adapter.as_deref(),
+            surface.as_ref(),
+        )
+        .await
+        .ok_or(error::Error::AdapterNotFound)?;
         log::debug!("{:?}", adapter.get_info());
         log::debug!("create device");
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor::default(), None)
             .await
-            .unwrap();
-        let inner_size = window.inner_size();
-        let config = surface
-            .get_default_config(&adapter, inner_size.width, inner_size.height)
-            .unwrap();
-        log::debug!("configure surface");
-        surface.configure(&device, &config);
+            .map_err(error::Error::Device)?;
+        let lost = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        device.on_uncaptured_error(Box::new({
+            let lost = lost.clone();
+            move |err| {
+                log::error!("gpu error: {}", err);
+                if let wgpu::Error::OutOfMemory { .. } = err {
+                    lost.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+        }));
+        let transparent = app_config.transparent || app_config.widget;
+        let mut config = match (&window, &surface) {
+            (Some(window), Some(surface)) => {
+                let inner_size = window.inner_size();
+                surface
+                    .get_default_config(&adapter, inner_size.width, inner_size.height)
+                    .ok_or_else(|| error::Error::AdapterUnsupported(adapter.get_info().name))?
+            }
+            _ => wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                width: app_config.size().0,
+                height: app_config.size().1,
+                present_mode: wgpu::PresentMode::Fifo,
+                alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+                view_formats: vec![],
+            },
+        };
+        if let Some(surface) = &surface {
+            let present_mode = app_config.present_mode.to_wgpu();
+            if surface
+                .get_capabilities(&adapter)
+                .present_modes
+                .contains(&present_mode)
+            {
+                config.present_mode = present_mode;
+            } else {
+                log::warn!("present mode {:?} is not supported", present_mode);
+            }
+        }
+        let mut brightness = 1.0;
+        if let (true, Some(surface)) = (app_config.hdr, &surface) {
+            // values of float surface are linear with 1.0 at SDR white of 80 nits (scRGB)
+            if surface
+                .get_capabilities(&adapter)
+                .formats
+                .contains(&wgpu::TextureFormat::Rgba16Float)
+            {
+                config.format = wgpu::TextureFormat::Rgba16Float;
+                brightness = app_config.hdr_white / gpu::SDR_WHITE;
+            } else {
+                log::warn!("hdr surface is not supported");
+            }
+        }
+        // shaders output linear colors, which a view of sRGB format encodes for display
+        if !config.format.is_srgb() && config.format != wgpu::TextureFormat::Rgba16Float {
+            let srgb_format = config.format.add_srgb_suffix();
+            if srgb_format != config.format {
+                config.view_formats = vec![srgb_format];
+            } else {
+                log::warn!("surface format {:?} is not sRGB", config.format);
+            }
+        }
+        let target_format = config
+            .view_formats
+            .first()
+            .copied()
+            .unwrap_or(config.format);
+        log::debug!(
+            "surface format {:?} drawn as {:?}",
+            config.format,
+            target_format
+        );
+        let sample_count = match app_config.msaa {
+            1 => 1,
+            sample_count
+                if [2, 4].contains(&sample_count)
+                    && adapter
+                        .get_texture_format_features(target_format)
+                        .flags
+                        .sample_count_supported(sample_count) =>
+            {
+                sample_count
+            }
+            sample_count => {
+                log::warn!("msaa {} is not supported", sample_count);
+                1
+            }
+        };
+        let multisampled = gpu::multisampled_view(
+            &device,
+            target_format,
+            (config.width, config.height),
+            sample_count,
+        );
+        if let (true, Some(surface)) = (transparent, &surface) {
+            let alpha_modes = surface.get_capabilities(&adapter).alpha_modes;
+            if let Some(alpha_mode) = [
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+            ]
+            .into_iter()
+            .find(|alpha_mode| alpha_modes.contains(alpha_mode))
+            {
+                config.alpha_mode = alpha_mode;
+            } else {
+                log::warn!("transparent window is not supported");
+            }
+        }
+        if let Some(surface) = &surface {
+            log::debug!("configure surface");
+            surface.configure(&device, &config);
+        }
 
         log::debug!("create pipelines");
-        let picture_pipeline =
-            picture::PicturePipeline::new(&device, config.format, picture_width, picture_height);
-        let text_pipeline =
-            text::TextPipeline::new(&device, config.format, config.width, config.height);
+        let mut picture_pipeline = picture::PicturePipeline::new(
+            &device,
+            target_format,
+            sample_count,
+            config.width,
+            config.height,
+            std::time::Duration::from_millis(app_config.picture_transition),
+            app_config.ken_burns_zoom,
+            std::time::Duration::from_secs(app_config.ken_burns_period),
+            app_config.picture_fit,
+            text::linear_color(app_config.letterbox_color.0),
+        );
+        let overlay_pipeline = overlay::OverlayPipeline::new(&device, target_format, sample_count);
+        let weather_icon_pipeline = icon::IconPipeline::new(
+            &device,
+            &queue,
+            target_format,
+            sample_count,
+            config.width,
+            config.height,
+            WEATHER_ICON_CENTER,
+            WEATHER_ICON_SIZE,
+        );
+        let moon_icon_pipeline = icon::IconPipeline::new(
+            &device,
+            &queue,
+            target_format,
+            sample_count,
+            config.width,
+            config.height,
+            MOON_ICON_CENTER,
+            MOON_ICON_SIZE,
+        );
+        let mut shader_pipeline = match &app_config.shader {
+            Some(path) if !transparent => {
+                let shader_pipeline = match std::fs::read_to_string(path) {
+                    Ok(source) => {
+                        shader::ShaderPipeline::new(
+                            &device,
+                            target_format,
+                            sample_count,
+                            config.width,
+                            config.height,
+                            &source,
+                            true,
+                        )
+                        .await
+                    }
+                    Err(err) => Err(err.into()),
+                };
+                match shader_pipeline {
+                    Ok(shader_pipeline) => Some(shader_pipeline),
+                    Err(err) => {
+                        log::error!("failed to load shader {}: {}", path, err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let mut text_pipeline = text::TextPipeline::new(
+            &device,
+            target_format,
+            sample_count,
+            config.width,
+            config.height,
+            app_config,
+        );
+        let blur_pipeline = (0.0 < app_config.blur && !transparent).then(|| {
+            blur::BlurPipeline::new(
+                &device,
+                target_format,
+                sample_count,
+                config.width,
+                config.height,
+                app_config.blur,
+            )
+        });
+        let scrim_pipeline = (0.0 < app_config.scrim_strength).then(|| {
+            scrim::ScrimPipeline::new(
+                &device,
+                target_format,
+                sample_count,
+                app_config.scrim_position,
+                app_config.scrim_strength,
+            )
+        });
+        if brightness != 1.0 {
+            picture_pipeline.set_hdr(brightness, app_config.hdr_peak / gpu::SDR_WHITE);
+            if let Some(shader_pipeline) = &mut shader_pipeline {
+                shader_pipeline.set_tint([brightness; 3]);
+            }
+            text_pipeline.set_tint([brightness; 3]);
+        }
 
-        Self {
+        Ok(Self {
             window,
             surface,
             device,
             queue,
             config,
             picture_pipeline,
+            overlay_pipeline,
+            weather_icon_pipeline,
+            moon_icon_pipeline,
+            shader_pipeline,
+            procedural: false,
             text_pipeline,
-        }
+            blur_pipeline,
+            blur_region: app_config.blur_region,
+            scrim_pipeline,
+            transparent,
+            dirty: true,
+            redraw_requested: std::cell::Cell::new(false),
+            lost,
+            brightness,
+            sample_count,
+            multisampled,
+            overlay: [0.0; 4],
+            tint: ([1.0; 3], [1.0; 3]),
+            shift: (0.0, 0.0),
+        })
     }
 
     fn request_redraw(&self) {
-        self.window.request_redraw();
+        if let Some(window) = &self.window {
+            self.redraw_requested.set(true);
+            window.request_redraw();
+        }
+    }
+
+    fn toggle_fullscreen(&self) {
+        if let Some(window) = &self.window {
+            window.toggle_fullscreen();
+        }
     }
 
     fn draw(&mut self) {
-        let frame = self.surface.get_current_texture().unwrap();
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        if let Some(new_inner_size) = self.window.as_mut().and_then(Window::poll_resize) {
+            self.resize(new_inner_size);
+        }
+        let Some(surface) = &self.surface else {
+            return;
+        };
+        // frame is skipped entirely when it would be identical to the shown one
+        let text_changed = self.text_pipeline.update();
+        let dirty = std::mem::take(&mut self.dirty) || !self.redraw_requested.replace(false);
+        if !text_changed && !dirty && !self.is_animating() {
+            return;
+        }
+        let instant = web_time::Instant::now();
+        let frame = match surface.get_current_texture() {
+            Ok(frame) => frame,
+            // surface goes stale on resume from suspend or monitor hotplug
+            Err(err @ (wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) => {
+                log::warn!("reconfigure surface: {}", err);
+                let inner_size = self.window.as_ref().unwrap().inner_size();
+                self.resize(inner_size);
+                self.dirty = true;
+                // minimized window is drawn again when restored
+                if 0 < inner_size.width && 0 < inner_size.height {
+                    self.request_redraw();
+                }
+                return;
+            }
+            Err(wgpu::SurfaceError::OutOfMemory) => {
+                log::error!("out of memory on acquiring frame");
+                self.lost.store(true, std::sync::atomic::Ordering::Relaxed);
+                return;
+            }
+            Err(wgpu::SurfaceError::Timeout) => {
+                log::debug!("skip frame on timeout");
+                self.dirty = true;
+                return;
+            }
+        };
+        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(self.target_format()),
+            ..Default::default()
+        });
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
-
-        self.picture_pipeline
-            .draw(&self.device, &view, &mut encoder);
-        self.text_pipeline.draw(&self.device, &view, &mut encoder);
+        self.encode(&view, &mut encoder);
 
         self.queue.submit([encoder.finish()]);
         frame.present();
+        metrics::record_frame(instant.elapsed());
+    }
+
+    /// Renders a frame into offscreen texture and reads it back
+    fn capture(&mut self) -> image::RgbaImage {
+        let (width, height) = (self.config.width, self.config.height);
+        let texture_size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: texture_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.target_format(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        self.text_pipeline.update();
+        self.encode(&view, &mut encoder);
+
+        // rows of buffer are padded to the alignment of copy
+        let hdr = self.target_format() == wgpu::TextureFormat::Rgba16Float;
+        let pixel_size = if hdr { 8 } else { 4 };
+        let bytes_per_row = (pixel_size * width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture_size,
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        self.device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        let bgra = matches!(
+            self.target_format(),
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        image::RgbaImage::from_fn(width, height, |x, y| {
+            let i = (y * bytes_per_row + x * pixel_size) as usize;
+            if hdr {
+                return gpu::hdr_to_srgb(&data[i..i + 8], self.brightness);
+            }
+            let [r, g, b, a] = [data[i], data[i + 1], data[i + 2], data[i + 3]];
+            if bgra {
+                image::Rgba([b, g, r, a])
+            } else {
+                image::Rgba([r, g, b, a])
+            }
+        })
+    }
+
+    fn encode(&mut self, output: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder) {
+        // multisampled texture is drawn into and resolved into output at the end
+        let multisampled = self.multisampled.take();
+        let view = multisampled.as_ref().unwrap_or(output);
+        if let Some(shader_pipeline) = &mut self.shader_pipeline {
+            shader_pipeline.update(&self.queue);
+        } else if !self.transparent {
+            self.picture_pipeline.update(&self.queue);
+        }
+
+        if self.transparent {
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+        } else {
+            // background is drawn again into blur pipeline, then blurred over the sharp one
+            let blur_target = self.blur_pipeline.as_ref().map(|blur| blur.target());
+            for view in std::iter::once(view).chain(blur_target) {
+                match &self.shader_pipeline {
+                    Some(shader_pipeline) => shader_pipeline.draw(&self.device, view, encoder),
+                    None => self.picture_pipeline.draw(&self.device, view, encoder),
+                }
+            }
+            if let Some(blur_pipeline) = &self.blur_pipeline {
+                match self.blur_region {
+                    blur::BlurRegion::Whole => blur_pipeline.draw(&self.queue, view, encoder, None),
+                    blur::BlurRegion::Text => {
+                        if let Some([left, top, right, bottom]) = self.text_pipeline.bounds() {
+                            let radius = blur_pipeline.radius();
+                            let region =
+                                [left - radius, top - radius, right + radius, bottom + radius];
+                            blur_pipeline.draw(&self.queue, view, encoder, Some(region));
+                        }
+                    }
+                }
+            }
+            if let Some(scrim_pipeline) = &self.scrim_pipeline {
+                scrim_pipeline.draw(&self.device, view, encoder);
+            }
+            self.overlay_pipeline.draw(&self.device, view, encoder);
+            self.weather_icon_pipeline.draw(&self.device, view, encoder);
+            self.moon_icon_pipeline.draw(&self.device, view, encoder);
+        }
+        self.text_pipeline
+            .draw(&self.device, &self.queue, view, encoder);
+
+        if let Some(multisampled) = &multisampled {
+            gpu::resolve(encoder, multisampled, output);
+        }
+        self.multisampled = multisampled;
     }
 
-    fn set_picture(&mut self, data: &[u8]) {
-        self.picture_pipeline.set_picture(&self.queue, data);
+    fn is_animating(&self) -> bool {
+        let background = match &self.shader_pipeline {
+            Some(shader_pipeline) => shader_pipeline.is_animating(),
+            None => !self.transparent && self.picture_pipeline.is_animating(),
+        };
+        background || self.text_pipeline.is_animating()
+    }
+
+    /// Replaces pictures with the built-in animated gradient
+    async fn set_procedural_background(&mut self) {
+        let mut shader_pipeline = shader::ShaderPipeline::new(
+            &self.device,
+            self.target_format(),
+            self.sample_count,
+            self.config.width,
+            self.config.height,
+            include_str!("../assets/shaders/gradient.wgsl"),
+            false,
+        )
+        .await
+        .unwrap();
+        let (tint, warmth) = self.tint;
+        shader_pipeline.set_tint([0, 1, 2].map(|i| tint[i] * warmth[i] * self.brightness));
+        self.shader_pipeline = Some(shader_pipeline);
+        self.procedural = true;
+        self.dirty = true;
+    }
+
+    /// Gives the background back to pictures found after all
+    fn clear_procedural_background(&mut self) {
+        if self.procedural {
+            self.shader_pipeline = None;
+            self.procedural = false;
+            self.dirty = true;
+        }
+    }
+
+    /// Format of views drawn into, which is sRGB unless the surface is float for HDR
+    fn target_format(&self) -> wgpu::TextureFormat {
+        self.config
+            .view_formats
+            .first()
+            .copied()
+            .unwrap_or(self.config.format)
+    }
+
+    fn is_lost(&self) -> bool {
+        self.lost.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Creates all resources again on the same window, after the device is lost
+    fn recreate(&mut self, app_config: &config::Config) -> Result<(), error::Error> {
+        let window = self.window.take();
+        // surface is released before another one is made for the window
+        self.surface = None;
+        *self = pollster::block_on(Renderer::new(window, app_config))?;
+        Ok(())
+    }
+
+    /// Whether a configured shader replaces pictures, not the procedural background
+    fn has_shader(&self) -> bool {
+        self.shader_pipeline.is_some() && !self.procedural
+    }
+
+    fn set_picture(&mut self, picture: picture::Picture) {
+        if let Some((image, _)) = picture.frames.first() {
+            self.text_pipeline.set_background(image);
+        }
+        self.text_pipeline.set_caption(picture.caption.clone());
+        self.picture_pipeline
+            .set_picture(&self.device, &self.queue, picture);
+        self.dirty = true;
+    }
+
+    fn prefetch_picture(&mut self, picture: &picture::Picture) {
+        self.picture_pipeline
+            .prefetch(&self.device, &self.queue, picture);
+    }
+
+    fn set_overlay(&mut self, color: [f32; 4]) {
+        if self.overlay != color {
+            self.overlay = color;
+            let [r, g, b, a] = color;
+            let brightness = self.brightness;
+            self.overlay_pipeline.set_color(
+                &self.queue,
+                [r * brightness, g * brightness, b * brightness, a],
+            );
+            self.dirty = true;
+        }
+    }
+
+    fn set_weather(&mut self, weather: &weather::Weather) {
+        self.weather_icon_pipeline
+            .set_icon(&self.device, &self.queue, weather.icon.as_ref());
+        self.dirty = true;
+        self.text_pipeline.set_weather(Some(weather.text.clone()));
+    }
+
+    fn set_event(&mut self, event: Option<&calendar::Event>) {
+        self.text_pipeline.set_event(event.cloned());
+    }
+
+    fn set_drift(&mut self, offset: Option<f64>) {
+        self.text_pipeline.set_drift(offset);
+    }
+
+    fn set_stats(&mut self, stats: Option<String>) {
+        self.text_pipeline.set_stats(stats);
+    }
+
+    fn set_battery(&mut self, battery: Option<battery::Battery>) {
+        self.text_pipeline.set_battery(battery);
+    }
+
+    fn set_moon(&mut self, phase: f64) {
+        let icon = moon::icon(phase, MOON_ICON_SIZE as u32 * 2);
+        self.moon_icon_pipeline
+            .set_icon(&self.device, &self.queue, Some(&icon));
+        self.dirty = true;
+        self.text_pipeline.set_moon(Some(moon::text(phase)));
+    }
+
+    fn set_message(&mut self, message: Option<String>) {
+        self.text_pipeline.set_message(message);
+    }
+
+    /// Rebuilds text pipeline with texts, colors and layout of reloaded config
+    fn reload(&mut self, config: &config::Config) {
+        let target_format = self.target_format();
+        self.text_pipeline
+            .reload(&self.device, target_format, self.sample_count, config);
+        self.dirty = true;
+    }
+
+    fn set_theme(&mut self, config: &config::Config) {
+        self.text_pipeline.set_text_color(config.text_color.0);
+        self.text_pipeline.set_greeting(config.greeting.clone());
+        self.dirty = true;
+    }
+
+    /// Tint applies to the whole output and warmth to picture only
+    fn set_tint(&mut self, tint: [f32; 3], warmth: [f32; 3]) {
+        if self.tint == (tint, warmth) {
+            return;
+        }
+        self.tint = (tint, warmth);
+        self.dirty = true;
+        let picture_tint = [0, 1, 2].map(|i| tint[i] * warmth[i]);
+        self.picture_pipeline.set_tint(picture_tint);
+        if let Some(shader_pipeline) = &mut self.shader_pipeline {
+            shader_pipeline.set_tint(picture_tint.map(|c| c * self.brightness));
+        }
+        self.text_pipeline
+            .set_tint(tint.map(|c| c * self.brightness));
+    }
+
+    fn set_shift(&mut self, shift: (f32, f32), picture: bool) {
+        self.text_pipeline.set_shift(shift);
+        if picture && self.shift != shift {
+            self.shift = shift;
+            self.picture_pipeline.set_shift(shift);
+            self.dirty = true;
+        }
+    }
+
+    fn set_stopwatch(&mut self, elapsed: Option<std::time::Duration>) {
+        self.text_pipeline.set_stopwatch(elapsed);
     }
 
     fn resize(&mut self, new_inner_size: winit::dpi::PhysicalSize<u32>) {
         if 0 < new_inner_size.width && 0 < new_inner_size.height {
             self.config.width = new_inner_size.width;
             self.config.height = new_inner_size.height;
-            self.surface.configure(&self.device, &self.config);
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+            self.multisampled = gpu::multisampled_view(
+                &self.device,
+                self.target_format(),
+                (new_inner_size.width, new_inner_size.height),
+                self.sample_count,
+            );
+            self.picture_pipeline
+                .resize(new_inner_size.width, new_inner_size.height);
+            if let Some(shader_pipeline) = &mut self.shader_pipeline {
+                shader_pipeline.resize(new_inner_size.width, new_inner_size.height);
+            }
+            self.weather_icon_pipeline.resize(
+                &self.queue,
+                new_inner_size.width,
+                new_inner_size.height,
+            );
+            self.moon_icon_pipeline.resize(
+                &self.queue,
+                new_inner_size.width,
+                new_inner_size.height,
+            );
             self.text_pipeline
                 .resize(new_inner_size.width, new_inner_size.height);
+            if let Some(blur_pipeline) = &mut self.blur_pipeline {
+                blur_pipeline.resize(&self.device, new_inner_size.width, new_inner_size.height);
+            }
+            self.dirty = true;
         }
     }
-
-    fn match_window(&self, window_id: winit::window::WindowId) -> bool {
-        self.window.id() == window_id
-    }
-}
-
-fn load_pictures(path: &str, width: u32, height: u32) -> Vec<Vec<u8>> {
-    log::debug!("load pictures");
-    std::fs::read_dir(path)
-        .unwrap()
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            entry
-                .file_name()
-                .to_str()
-                .map_or(false, |name| !name.starts_with('.'))
-        })
-        .filter_map(|entry| image::open(entry.path()).ok())
-        .map(|img| {
-            img.resize_to_fill(width, height, image::imageops::Lanczos3)
-                .to_rgba8()
-                .to_vec()
-        })
-        .collect()
 }
@@ -0,0 +1,125 @@
+/// Health of the running instance, exposed in Prometheus text format
+static METRICS: std::sync::Mutex<Metrics> = std::sync::Mutex::new(Metrics {
+    frame: Histogram::new(&[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]),
+    picture_load: Histogram::new(&[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+    picture_store_bytes: 0,
+    last_redraw: None,
+});
+
+struct Metrics {
+    frame: Histogram,
+    picture_load: Histogram,
+    picture_store_bytes: u64,
+    last_redraw: Option<web_time::SystemTime>,
+}
+
+struct Histogram {
+    /// Upper bounds of buckets [s]
+    bounds: &'static [f64],
+    counts: [u64; 16],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    const fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: [0; 16],
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(bucket) = self.bounds.iter().position(|&bound| value <= bound) {
+            self.counts[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    fn write(&self, out: &mut String, name: &str, help: &str) {
+        use std::fmt::Write;
+
+        writeln!(out, "# HELP {} {}", name, help).unwrap();
+        writeln!(out, "# TYPE {} histogram", name).unwrap();
+        // buckets are cumulative
+        let mut count = 0;
+        for (bound, bucket_count) in self.bounds.iter().zip(self.counts) {
+            count += bucket_count;
+            writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count).unwrap();
+        }
+        writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count).unwrap();
+        writeln!(out, "{}_sum {}", name, self.sum).unwrap();
+        writeln!(out, "{}_count {}", name, self.count).unwrap();
+    }
+}
+
+/// Records a frame drawn in the duration
+pub fn record_frame(duration: std::time::Duration) {
+    let mut metrics = METRICS.lock().unwrap();
+    metrics.frame.observe(duration.as_secs_f64());
+    metrics.last_redraw = Some(web_time::SystemTime::now());
+}
+
+/// Records a picture read and decoded in the duration
+pub fn record_picture_load(duration: std::time::Duration) {
+    METRICS
+        .lock()
+        .unwrap()
+        .picture_load
+        .observe(duration.as_secs_f64());
+}
+
+/// Sets bytes of decoded pictures held by all picture stores
+pub fn set_picture_store_bytes(bytes: u64) {
+    METRICS.lock().unwrap().picture_store_bytes = bytes;
+}
+
+/// Metrics in Prometheus text exposition format
+pub fn render() -> String {
+    use std::fmt::Write;
+
+    let metrics = METRICS.lock().unwrap();
+    let mut out = String::new();
+    metrics.frame.write(
+        &mut out,
+        "clock_app_frame_seconds",
+        "Time to draw and present a frame.",
+    );
+    metrics.picture_load.write(
+        &mut out,
+        "clock_app_picture_load_seconds",
+        "Time to read and decode a picture.",
+    );
+    writeln!(
+        out,
+        "# HELP clock_app_picture_store_bytes Memory of decoded pictures held by picture stores."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE clock_app_picture_store_bytes gauge").unwrap();
+    writeln!(
+        out,
+        "clock_app_picture_store_bytes {}",
+        metrics.picture_store_bytes
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "# HELP clock_app_last_redraw_timestamp_seconds Unix time of the last frame drawn."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE clock_app_last_redraw_timestamp_seconds gauge").unwrap();
+    let last_redraw = metrics
+        .last_redraw
+        .and_then(|time| time.duration_since(web_time::UNIX_EPOCH).ok())
+        .map_or(0.0, |time| time.as_secs_f64());
+    writeln!(
+        out,
+        "clock_app_last_redraw_timestamp_seconds {}",
+        last_redraw
+    )
+    .unwrap();
+    out
+}
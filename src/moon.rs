@@ -0,0 +1,50 @@
+/// Mean length of lunar month [day]
+const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// New moon of 2000-01-06 18:14 UTC [unix time]
+const NEW_MOON: i64 = 947182440;
+
+/// Age of moon as a fraction of lunar month, 0 is new moon and 0.5 is full moon
+pub fn phase(now: chrono::DateTime<chrono::Utc>) -> f64 {
+    let days = (now.timestamp() - NEW_MOON) as f64 / 86400.0;
+    (days / SYNODIC_MONTH).rem_euclid(1.0)
+}
+
+/// Name of phase followed by illuminated fraction of disk
+pub fn text(phase: f64) -> String {
+    let names = [
+        "new moon",
+        "waxing crescent",
+        "first quarter",
+        "waxing gibbous",
+        "full moon",
+        "waning gibbous",
+        "last quarter",
+        "waning crescent",
+    ];
+    let name = names[(phase * 8.0 + 0.5) as usize % 8];
+    let illumination = (1.0 - (phase * std::f64::consts::TAU).cos()) * 0.5;
+    format!("{} {:.0}%", name, illumination * 100.0)
+}
+
+/// Disk lit as seen from northern hemisphere, dark side is faint
+pub fn icon(phase: f64, size: u32) -> image::RgbaImage {
+    let radius = size as f64 * 0.5;
+    let terminator = (phase * std::f64::consts::TAU).cos();
+    image::RgbaImage::from_fn(size, size, |x, y| {
+        let (x, y) = (x as f64 + 0.5 - radius, y as f64 + 0.5 - radius);
+        let distance = (x * x + y * y).sqrt();
+        // one pixel wide edges are blended for antialiasing
+        let disk = (radius - distance).clamp(0.0, 1.0);
+        let half_width = (radius * radius - y * y).max(0.0).sqrt();
+        let lit = if phase < 0.5 {
+            x - half_width * terminator
+        } else {
+            -half_width * terminator - x
+        };
+        let lit = lit.clamp(0.0, 1.0);
+        let alpha = disk * (0.2 + 0.8 * lit);
+        let [r, g, b] = [255.0, 250.0, 220.0].map(|c| c * (0.3 + 0.7 * lit));
+        image::Rgba([r as u8, g as u8, b as u8, (alpha * 255.0) as u8])
+    })
+}
@@ -0,0 +1,117 @@
+/// Connection settings of MQTT broker
+pub struct Broker {
+    pub host: String,
+    pub port: u16,
+    pub credentials: Option<(String, String)>,
+    /// Prefix of command and state topics
+    pub topic: String,
+}
+
+/// Subscribes command topics under the prefix and publishes status to `<prefix>/state` when it changes
+pub fn spawn(
+    broker: Broker,
+    status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+    interval: std::time::Duration,
+) -> std::sync::mpsc::Receiver<crate::control::Command> {
+    use rumqttc::QoS;
+
+    let topic = broker.topic;
+    let state_topic = format!("{}/state", topic);
+    let availability_topic = format!("{}/availability", topic);
+    let mut options = rumqttc::MqttOptions::new("clock-app", broker.host, broker.port);
+    options.set_keep_alive(std::time::Duration::from_secs(30));
+    options.set_last_will(rumqttc::LastWill::new(
+        &availability_topic,
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some((username, password)) = broker.credentials {
+        options.set_credentials(username, password);
+    }
+    let (client, mut connection) = rumqttc::Client::new(options, 16);
+
+    let publisher = client.clone();
+    std::thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let status = status.lock().unwrap().clone();
+            if last.as_ref() != Some(&status) {
+                let payload = serde_json::to_string(&status).unwrap();
+                match publisher.publish(&state_topic, QoS::AtLeastOnce, true, payload) {
+                    Ok(()) => last = Some(status),
+                    Err(err) => log::error!("failed to publish mqtt state: {}", err),
+                }
+            }
+            std::thread::sleep(interval);
+        }
+    });
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        use rumqttc::Event;
+        use rumqttc::Packet;
+
+        for notification in connection.iter() {
+            match notification {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    log::debug!("connect mqtt broker");
+                    // subscriptions are lost on reconnection of clean session
+                    let subscribed = client
+                        .try_subscribe(format!("{}/+/+", topic), QoS::AtLeastOnce)
+                        .and_then(|()| {
+                            client.try_publish(
+                                &availability_topic,
+                                QoS::AtLeastOnce,
+                                true,
+                                "online",
+                            )
+                        });
+                    if let Err(err) = subscribed {
+                        log::error!("failed to subscribe mqtt topics: {}", err);
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let payload = String::from_utf8_lossy(&publish.payload);
+                    let subtopic = publish.topic.strip_prefix(&topic).unwrap_or_default();
+                    match parse(subtopic, payload.trim()) {
+                        Some(command) => {
+                            if sender.send(command).is_err() {
+                                break;
+                            }
+                        }
+                        None => log::warn!("unknown mqtt command {}", publish.topic),
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    log::error!("failed to connect mqtt broker: {}", err);
+                    std::thread::sleep(std::time::Duration::from_secs(5));
+                }
+            }
+        }
+    });
+    receiver
+}
+
+fn parse(subtopic: &str, payload: &str) -> Option<crate::control::Command> {
+    use crate::control::Command;
+
+    match subtopic {
+        "/picture/next" => Some(Command::NextPicture),
+        "/picture/previous" => Some(Command::PreviousPicture),
+        "/message/set" => Some(Command::SetMessage(
+            (!payload.is_empty()).then(|| payload.to_string()),
+        )),
+        "/profile/set" => Some(Command::SetProfile(
+            (!payload.is_empty()).then(|| payload.to_string()),
+        )),
+        "/brightness/set" => payload.parse().ok().map(Command::SetBrightness),
+        "/alarm/set" => match payload.to_ascii_lowercase().as_str() {
+            "on" => Some(Command::TriggerAlarm),
+            "off" => Some(Command::DismissAlarm),
+            _ => None,
+        },
+        _ => None,
+    }
+}
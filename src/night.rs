@@ -0,0 +1,88 @@
+/// Daily period written as `HH:MM-HH:MM`, which may wrap around midnight
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct NightSchedule {
+    start: chrono::NaiveTime,
+    end: chrono::NaiveTime,
+}
+
+impl NightSchedule {
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+impl std::str::FromStr for NightSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |time: &str| {
+            chrono::NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|_| format!("invalid night time: {:?}", time))
+        };
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("invalid night schedule: {:?}", s))?;
+        Ok(Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+impl TryFrom<String> for NightSchedule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Clone)]
+pub struct NightMode {
+    schedule: NightSchedule,
+    timezone: Option<chrono_tz::Tz>,
+    brightness: f32,
+    red_shift: bool,
+}
+
+impl NightMode {
+    pub fn new(
+        schedule: NightSchedule,
+        timezone: Option<chrono_tz::Tz>,
+        brightness: f32,
+        red_shift: bool,
+    ) -> Self {
+        Self {
+            schedule,
+            timezone,
+            brightness,
+            red_shift,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        let now = match self.timezone {
+            Some(timezone) => chrono::Utc::now().with_timezone(&timezone).time(),
+            None => chrono::Local::now().time(),
+        };
+        self.schedule.contains(now)
+    }
+
+    /// Color multiplier applied to whole output, white outside the schedule
+    pub fn tint(&self) -> [f32; 3] {
+        if !self.is_active() {
+            return [1.0; 3];
+        }
+        let brightness = self.brightness;
+        if self.red_shift {
+            [brightness, brightness * 0.3, brightness * 0.1]
+        } else {
+            [brightness; 3]
+        }
+    }
+}
@@ -0,0 +1,260 @@
+/// Floats of an instance: rect and digit, alpha and aspect of sprite cell
+const INSTANCE_LEN: usize = 8;
+
+/// Nixie tubes of digits, each drawn from a cell of a sprite sheet with warm glow
+pub struct NixiePipeline {
+    sheet: wgpu::Texture,
+    /// Sprite sheet to be written into the texture on the first use
+    pending: Option<image::RgbaImage>,
+    /// Height of a cell relative to its width
+    aspect: f32,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+    instances: Vec<[f32; INSTANCE_LEN]>,
+}
+
+impl NixiePipeline {
+    /// Sprite sheet has cells of digits 0 to 9 side by side, whose brightness lights the tube,
+    /// built from the font if no path is given
+    pub fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+        font: &wgpu_glyph::ab_glyph::FontArc,
+        sprites: Option<&str>,
+    ) -> Self {
+        let image = sprites
+            .and_then(|path| match image::open(path) {
+                Ok(image) if 10 <= image.width() => Some(image.to_rgba8()),
+                Ok(image) => {
+                    log::error!("nixie sprites {} are too narrow: {}", path, image.width());
+                    None
+                }
+                Err(err) => {
+                    log::error!(
+                        "failed to load nixie sprites {}, use built-in digits: {}",
+                        path,
+                        err
+                    );
+                    None
+                }
+            })
+            .unwrap_or_else(|| crate::face::digit_sheet(font, 0.8));
+        let (width, height) = image.dimensions();
+
+        let sheet = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let sheet_view = sheet.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&sheet_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let shader =
+            device.create_shader_module(wgpu::include_wgsl!("../assets/shaders/nixie.wgsl"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; INSTANCE_LEN]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x4,
+                        1 => Float32x4,
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        Self {
+            sheet,
+            pending: Some(image),
+            aspect: height as f32 / (width / 10) as f32,
+            bind_group,
+            pipeline,
+            instances: vec![],
+        }
+    }
+
+    /// Height of a tube relative to its width
+    pub fn aspect(&self) -> f32 {
+        self.aspect
+    }
+
+    /// Queues tube of digit within rect [px]
+    pub fn queue(
+        &mut self,
+        queue: &wgpu::Queue,
+        digit: u32,
+        [left, top, right, bottom]: [f32; 4],
+        alpha: f32,
+        (target_width, target_height): (u32, u32),
+    ) {
+        if let Some(image) = self.pending.take() {
+            let (width, height) = image.dimensions();
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.sheet,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &image,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * width),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let to_clip = |x: f32, y: f32| {
+            (
+                x / target_width as f32 * 2.0 - 1.0,
+                1.0 - y / target_height as f32 * 2.0,
+            )
+        };
+        let (left, top) = to_clip(left, top);
+        let (right, bottom) = to_clip(right, bottom);
+        self.instances.push([
+            left,
+            top,
+            right,
+            bottom,
+            digit as f32,
+            alpha,
+            self.aspect,
+            0.0,
+        ]);
+    }
+
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        use wgpu::util::DeviceExt;
+
+        let instances = std::mem::take(&mut self.instances);
+        if instances.is_empty() {
+            return;
+        }
+
+        let data = instances
+            .iter()
+            .flatten()
+            .flat_map(|c| c.to_ne_bytes())
+            .collect::<Vec<_>>();
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &data,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, instance_buffer.slice(..));
+        pass.draw(0..4, 0..instances.len() as u32);
+    }
+}
@@ -0,0 +1,59 @@
+/// Seconds between NTP epoch (1900) and UNIX epoch (1970)
+const NTP_UNIX_OFFSET: f64 = 2208988800.0;
+
+pub fn spawn(server: String, interval: std::time::Duration) -> std::sync::mpsc::Receiver<f64> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || loop {
+        log::debug!("query ntp server");
+        match query(&server) {
+            Ok(offset) => {
+                log::debug!("ntp offset {:+.3}s", offset);
+                if sender.send(offset).is_err() {
+                    break;
+                }
+            }
+            Err(err) => log::error!("failed to query ntp server {}: {}", server, err),
+        }
+        std::thread::sleep(interval);
+    });
+    receiver
+}
+
+fn query(server: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    let address = if server.contains(':') {
+        server.to_string()
+    } else {
+        format!("{}:123", server)
+    };
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+    socket.connect(address)?;
+
+    // LI = 0, VN = 3, Mode = 3 (client)
+    let mut packet = [0u8; 48];
+    packet[0] = 0x1b;
+    let originate = unix_now();
+    socket.send(&packet)?;
+    let len = socket.recv(&mut packet)?;
+    let destination = unix_now();
+    if len < 48 {
+        return Err("short ntp response".into());
+    }
+
+    let receive = timestamp(&packet[32..40]);
+    let transmit = timestamp(&packet[40..48]);
+    Ok(((receive - originate) + (transmit - destination)) * 0.5)
+}
+
+fn timestamp(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    seconds - NTP_UNIX_OFFSET + fraction / 4294967296.0
+}
+
+fn unix_now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
@@ -0,0 +1,39 @@
+/// Side of the image sampled for palette [px]
+const SAMPLE_SIZE: u32 = 32;
+
+/// Most common color of image, favoring saturated colors over grays
+pub fn dominant(image: &image::RgbaImage) -> [f32; 3] {
+    let sample = image::imageops::thumbnail(image, SAMPLE_SIZE, SAMPLE_SIZE);
+
+    // colors are counted in bins of 3 bits per channel
+    let mut bins = std::collections::HashMap::<_, (f32, [f32; 3], u32)>::new();
+    for pixel in sample.pixels() {
+        let [r, g, b, _] = pixel.0;
+        let color = [r, g, b].map(|channel| channel as f32 / 255.0);
+        let max = color.into_iter().fold(0.0, f32::max);
+        let min = color.into_iter().fold(1.0, f32::min);
+        let saturation = if 0.0 < max { (max - min) / max } else { 0.0 };
+
+        let (weight, sum, count) = bins.entry((r >> 5, g >> 5, b >> 5)).or_default();
+        *weight += 0.1 + saturation;
+        for i in 0..3 {
+            sum[i] += color[i];
+        }
+        *count += 1;
+    }
+
+    bins.into_values()
+        .max_by(|(a, _, _), (b, _, _)| a.total_cmp(b))
+        .map(|(_, sum, count)| sum.map(|channel| channel / count as f32))
+        .unwrap_or([1.0; 3])
+}
+
+/// Color mixed with white, used for texts
+pub fn light(color: [f32; 3]) -> [f32; 3] {
+    color.map(|channel| channel * 0.4 + 0.6)
+}
+
+/// Color mixed with black, used for outlines and shadows
+pub fn dark(color: [f32; 3]) -> [f32; 3] {
+    color.map(|channel| channel * 0.25)
+}
@@ -1,32 +1,122 @@
+/// Frames of a picture with their display durations, a still picture has just one frame
+#[derive(Clone)]
+pub struct Picture {
+    /// Path or URL the picture is loaded from
+    pub name: String,
+    /// Unique to every decoding, as names are shared by pictures of the same source
+    pub serial: u64,
+    /// Title and date taken read from metadata
+    pub caption: Option<String>,
+    pub frames: Vec<(image::RgbaImage, std::time::Duration)>,
+    /// Mip levels below each frame, made by [`mipmap`] off the render thread
+    pub mipmaps: Vec<Vec<image::RgbaImage>>,
+    /// Display duration and transition overriding the options, set in manifest
+    pub duration: Option<std::time::Duration>,
+    pub transition: Option<std::time::Duration>,
+}
+
+/// How a picture is placed on the window
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PictureFit {
+    /// Fill the window, cropping overflowing sides
+    Cover,
+    /// Show the whole picture, letterboxing the rest
+    Contain,
+    /// Fill the window, ignoring aspect ratio
+    Stretch,
+    /// Repeat the picture at its pixel size
+    Tile,
+    /// Show the picture at its pixel size in the middle, letterboxing the rest
+    Center,
+}
+
 pub struct PicturePipeline {
-    texture: wgpu::Texture,
+    previous_texture: wgpu::Texture,
+    current_texture: wgpu::Texture,
+    /// Next picture uploaded ahead by serial, so that switching to it does not stall a frame
+    standby: Option<(u64, wgpu::Texture)>,
+    uniform_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
     bind_group: wgpu::BindGroup,
     pipeline: wgpu::RenderPipeline,
+    target_width: u32,
+    target_height: u32,
+    transition: std::time::Duration,
+    /// Transition to the current picture, which may override the default one
+    current_transition: std::time::Duration,
+    transition_instant: Option<web_time::Instant>,
+    ken_burns_zoom: f32,
+    ken_burns_period: std::time::Duration,
+    previous_motion: Motion,
+    current_motion: Motion,
+    frames: Vec<(image::RgbaImage, std::time::Duration)>,
+    mipmaps: Vec<Vec<image::RgbaImage>>,
+    frame_index: usize,
+    frame_instant: web_time::Instant,
+    fit: PictureFit,
+    /// Linear color around pictures not covering the window
+    letterbox_color: [f32; 4],
+    tint: [f32; 3],
+    /// White and peak levels relative to SDR white
+    hdr: (f32, f32),
+    shift: (f32, f32),
+}
+
+/// Ken Burns motion of a picture, zooming toward the pan direction
+#[derive(Clone, Copy)]
+struct Motion {
+    instant: web_time::Instant,
+    pan: [f32; 2],
+}
+
+impl Motion {
+    fn new() -> Self {
+        Self {
+            instant: web_time::Instant::now(),
+            pan: [
+                rand::random::<f32>() * 2.0 - 1.0,
+                rand::random::<f32>() * 2.0 - 1.0,
+            ],
+        }
+    }
 }
 
 impl PicturePipeline {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: &wgpu::Device,
         target_format: wgpu::TextureFormat,
-        picture_width: u32,
-        picture_height: u32,
+        sample_count: u32,
+        target_width: u32,
+        target_height: u32,
+        transition: std::time::Duration,
+        ken_burns_zoom: f32,
+        ken_burns_period: std::time::Duration,
+        fit: PictureFit,
+        letterbox_color: [f32; 4],
     ) -> Self {
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
+        let previous_texture = create_texture(device, 1, 1);
+        let current_texture = create_texture(device, 1, 1);
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: None,
-            size: wgpu::Extent3d {
-                width: picture_width,
-                height: picture_height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
+            size: std::mem::size_of::<[f32; 24]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let address_mode = match fit {
+            PictureFit::Tile => wgpu::AddressMode::Repeat,
+            _ => wgpu::AddressMode::ClampToEdge,
+        };
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
         });
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
 
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: None,
@@ -44,28 +134,42 @@ impl PicturePipeline {
                 wgpu::BindGroupLayoutEntry {
                     binding: 1,
                     visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
                     count: None,
                 },
-            ],
-        });
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: None,
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
                 },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
             ],
         });
+        let bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            &previous_texture,
+            &current_texture,
+            &sampler,
+            &uniform_buffer,
+        );
 
         let shader =
-            device.create_shader_module(wgpu::include_wgsl!("../assets/shaders/main.wgsl"));
+            device.create_shader_module(wgpu::include_wgsl!("../assets/shaders/picture.wgsl"));
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: None,
@@ -100,7 +204,7 @@ impl PicturePipeline {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -108,25 +212,204 @@ impl PicturePipeline {
         });
 
         Self {
-            texture,
+            previous_texture,
+            current_texture,
+            standby: None,
+            uniform_buffer,
+            sampler,
+            bind_group_layout,
             bind_group,
             pipeline,
+            target_width,
+            target_height,
+            transition,
+            current_transition: transition,
+            transition_instant: None,
+            ken_burns_zoom,
+            // zero period would divide by zero
+            ken_burns_period: ken_burns_period.max(std::time::Duration::from_secs(1)),
+            previous_motion: Motion::new(),
+            current_motion: Motion::new(),
+            frames: vec![],
+            mipmaps: vec![],
+            frame_index: 0,
+            frame_instant: web_time::Instant::now(),
+            fit,
+            letterbox_color,
+            tint: [1.0; 3],
+            hdr: (1.0, 1.0),
+            shift: (0.0, 0.0),
         }
     }
 
-    pub fn set_picture(&mut self, queue: &wgpu::Queue, data: &[u8]) {
-        let texture_size = self.texture.size();
+    /// Uploads the picture to be set next into a texture on standby
+    pub fn prefetch(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, picture: &Picture) {
+        if self
+            .standby
+            .as_ref()
+            .is_some_and(|(serial, _)| *serial == picture.serial)
+        {
+            return;
+        }
+        let (image, _) = &picture.frames[0];
+        let texture = create_texture(device, image.width(), image.height());
+        write_texture(queue, &texture, image, &picture.mipmaps[0]);
+        self.standby = Some((picture.serial, texture));
+    }
 
-        queue.write_texture(
-            self.texture.as_image_copy(),
-            data,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * texture_size.width),
-                rows_per_image: Some(texture_size.height),
-            },
-            texture_size,
+    pub fn set_picture(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, picture: Picture) {
+        let (image, _) = &picture.frames[0];
+
+        let texture = match self.standby.take() {
+            Some((serial, texture)) if serial == picture.serial => texture,
+            standby => {
+                self.standby = standby;
+                // reuse texture of previous picture if the size matches
+                let texture_size = self.previous_texture.size();
+                let texture = if texture_size.width == image.width()
+                    && texture_size.height == image.height()
+                {
+                    std::mem::replace(&mut self.previous_texture, create_texture(device, 1, 1))
+                } else {
+                    create_texture(device, image.width(), image.height())
+                };
+                write_texture(queue, &texture, image, &picture.mipmaps[0]);
+                texture
+            }
+        };
+
+        self.previous_texture = std::mem::replace(&mut self.current_texture, texture);
+        self.bind_group = create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.previous_texture,
+            &self.current_texture,
+            &self.sampler,
+            &self.uniform_buffer,
         );
+        self.current_transition = picture.transition.unwrap_or(self.transition);
+        self.transition_instant = Some(web_time::Instant::now());
+        self.previous_motion = std::mem::replace(&mut self.current_motion, Motion::new());
+        self.frames = picture.frames;
+        self.mipmaps = picture.mipmaps;
+        self.frame_index = 0;
+        self.frame_instant = web_time::Instant::now();
+    }
+
+    pub fn resize(&mut self, target_width: u32, target_height: u32) {
+        self.target_width = target_width;
+        self.target_height = target_height;
+    }
+
+    /// Color multiplier of picture, applied on next update
+    pub fn set_tint(&mut self, tint: [f32; 3]) {
+        self.tint = tint;
+    }
+
+    /// Levels of white and highlights on HDR surface, relative to SDR white
+    pub fn set_hdr(&mut self, white: f32, peak: f32) {
+        self.hdr = (white, peak);
+    }
+
+    /// Offset of whole picture [px]
+    pub fn set_shift(&mut self, shift: (f32, f32)) {
+        self.shift = shift;
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.transition_instant.is_some() || 0.0 < self.ken_burns_zoom || 1 < self.frames.len()
+    }
+
+    pub fn update(&mut self, queue: &wgpu::Queue) {
+        if 1 < self.frames.len() {
+            let (_, delay) = self.frames[self.frame_index];
+            if delay <= self.frame_instant.elapsed() {
+                self.frame_index = (self.frame_index + 1) % self.frames.len();
+                self.frame_instant += delay;
+                write_texture(
+                    queue,
+                    &self.current_texture,
+                    &self.frames[self.frame_index].0,
+                    &self.mipmaps[self.frame_index],
+                );
+            }
+        }
+
+        let blend = match self.transition_instant {
+            Some(instant) if instant.elapsed() < self.current_transition => {
+                instant.elapsed().as_secs_f32() / self.current_transition.as_secs_f32()
+            }
+            _ => {
+                self.transition_instant = None;
+                1.0
+            }
+        };
+
+        let previous_transform = self.transform(&self.previous_texture, self.previous_motion);
+        let current_transform = self.transform(&self.current_texture, self.current_motion);
+        let uniform = [
+            previous_transform[0],
+            previous_transform[1],
+            previous_transform[2],
+            previous_transform[3],
+            current_transform[0],
+            current_transform[1],
+            current_transform[2],
+            current_transform[3],
+            blend,
+            self.hdr.0,
+            self.hdr.1,
+            0.0,
+            self.tint[0],
+            self.tint[1],
+            self.tint[2],
+            0.0,
+            self.letterbox_color[0],
+            self.letterbox_color[1],
+            self.letterbox_color[2],
+            self.letterbox_color[3],
+            (self.fit == PictureFit::Tile) as u32 as f32,
+            0.0,
+            0.0,
+            0.0,
+        ];
+        let data = uniform
+            .iter()
+            .flat_map(|c| c.to_ne_bytes())
+            .collect::<Vec<_>>();
+        queue.write_buffer(&self.uniform_buffer, 0, &data);
+    }
+
+    fn transform(&self, texture: &wgpu::Texture, motion: Motion) -> [f32; 4] {
+        // scale and offset of texcoords placing the picture on the target, texcoords out of
+        // 0..1 show letterbox
+        let texture_size = texture.size();
+        let (scale_x, scale_y) = fit_scale(
+            self.fit,
+            (texture_size.width, texture_size.height),
+            (self.target_width, self.target_height),
+        );
+
+        // zoom in and out smoothly within the cropped region
+        let zoom = if 0.0 < self.ken_burns_zoom {
+            let phase =
+                motion.instant.elapsed().as_secs_f32() / self.ken_burns_period.as_secs_f32();
+            1.0 + self.ken_burns_zoom * (0.5 - 0.5 * (phase * std::f32::consts::TAU).cos())
+        } else {
+            1.0
+        };
+        let margin = 1.0 - 1.0 / zoom;
+        let pan_x = margin * (0.5 + 0.5 * motion.pan[0]);
+        let pan_y = margin * (0.5 + 0.5 * motion.pan[1]);
+
+        let shift_x = self.shift.0 / self.target_width as f32;
+        let shift_y = self.shift.1 / self.target_height as f32;
+        [
+            scale_x / zoom,
+            scale_y / zoom,
+            (1.0 - scale_x) * 0.5 + scale_x * pan_x - shift_x * scale_x / zoom,
+            (1.0 - scale_y) * 0.5 + scale_y * pan_y - shift_y * scale_y / zoom,
+        ]
     }
 
     pub fn draw(
@@ -149,3 +432,129 @@ impl PicturePipeline {
         pass.draw(0..3, 0..1);
     }
 }
+
+/// Scale of texcoords placing the picture on the target around their center, the picture spans
+/// texcoords 0..1 and the rest shows letterbox (or repeats for tile)
+pub fn fit_scale(
+    fit: PictureFit,
+    (width, height): (u32, u32),
+    (target_width, target_height): (u32, u32),
+) -> (f32, f32) {
+    let picture_aspect = width as f32 / height as f32;
+    let target_aspect = target_width as f32 / target_height as f32;
+    match fit {
+        PictureFit::Cover if target_aspect < picture_aspect => {
+            (target_aspect / picture_aspect, 1.0)
+        }
+        PictureFit::Cover => (1.0, picture_aspect / target_aspect),
+        PictureFit::Contain if target_aspect < picture_aspect => {
+            (1.0, picture_aspect / target_aspect)
+        }
+        PictureFit::Contain => (target_aspect / picture_aspect, 1.0),
+        PictureFit::Stretch => (1.0, 1.0),
+        PictureFit::Tile | PictureFit::Center => (
+            target_width as f32 / width as f32,
+            target_height as f32 / height as f32,
+        ),
+    }
+}
+
+fn create_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        // mipmaps down to 1px keep pictures drawn smaller than the texture from aliasing
+        mip_level_count: u32::BITS - width.max(height).leading_zeros(),
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+/// Halved copies of the image down to 1px, sized as the mip levels of its texture
+pub fn mipmap(image: &image::RgbaImage) -> Vec<image::RgbaImage> {
+    let (width, height) = image.dimensions();
+    let mip_level_count = u32::BITS - width.max(height).leading_zeros();
+    let mut levels = Vec::<image::RgbaImage>::new();
+    for mip_level in 1..mip_level_count {
+        let level = image::imageops::resize(
+            levels.last().unwrap_or(image),
+            (width >> mip_level).max(1),
+            (height >> mip_level).max(1),
+            image::imageops::FilterType::Triangle,
+        );
+        levels.push(level);
+    }
+    levels
+}
+
+/// Writes the image into the base level and its mipmaps into the other levels
+fn write_texture(
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    image: &image::RgbaImage,
+    mipmaps: &[image::RgbaImage],
+) {
+    for (mip_level, level_image) in std::iter::once(image).chain(mipmaps).enumerate() {
+        let mip_level = mip_level as u32;
+        let size = texture
+            .size()
+            .mip_level_size(mip_level, wgpu::TextureDimension::D2);
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            level_image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * size.width),
+                rows_per_image: Some(size.height),
+            },
+            size,
+        );
+    }
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    previous_texture: &wgpu::Texture,
+    current_texture: &wgpu::Texture,
+    sampler: &wgpu::Sampler,
+    uniform_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    let previous_texture_view =
+        previous_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let current_texture_view = current_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&previous_texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&current_texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
@@ -0,0 +1,1243 @@
+#[derive(Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PictureOrder {
+    Sequential,
+    Shuffle,
+    ShuffleNoRepeat,
+}
+
+/// Number of shown pictures kept for going back
+const HISTORY_SIZE: usize = 10;
+
+/// Number of decoded pictures kept, the others in history are decoded again when going back
+const DECODED_SIZE: usize = 2;
+
+/// Wait before loading a failed remote source again, doubled on every failure up to the max
+const RETRY_MIN: std::time::Duration = std::time::Duration::from_secs(5);
+const RETRY_MAX: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Serial of the next decoded picture
+static SERIAL: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+pub struct PictureStore {
+    receiver: std::sync::mpsc::Receiver<(crate::picture::Picture, Recall, Option<Moment>)>,
+    /// Upcoming picture taken from decoder ahead of showing it
+    pending: Option<(crate::picture::Picture, Recall, Option<Moment>)>,
+    _watcher: Option<notify::RecommendedWatcher>,
+    /// Shown pictures by id, kept undecoded
+    history: std::collections::VecDeque<(u64, Recall)>,
+    /// Steps back from the latest picture in history
+    position: usize,
+    /// Decoded pictures of history by id, least recently used first
+    decoded: std::collections::VecDeque<(u64, crate::picture::Picture)>,
+    next_id: u64,
+    /// Pictures of history sent to be decoded again off the event loop, and their results
+    recall_sender: std::sync::mpsc::Sender<(u64, Recall)>,
+    recalled: std::sync::mpsc::Receiver<(u64, Option<crate::picture::Picture>)>,
+    /// Id of the picture gone back or forward to, shown once it is decoded again
+    recalling: Option<u64>,
+    /// Current weather condition, shared with decoder
+    weather: std::sync::Arc<std::sync::Mutex<Option<crate::weather::Condition>>>,
+    timezone: Option<chrono_tz::Tz>,
+}
+
+impl PictureStore {
+    /// `None` when no picture is found
+    pub fn new(config: &crate::config::Config) -> Result<Option<Self>, crate::error::Error> {
+        let path = config.picture_path.as_str();
+        let (width, height) = (config.picture_width, config.picture_height);
+        let order = config.picture_order;
+
+        let cache = config.picture_cache.as_ref().map(std::path::PathBuf::from);
+        if let Some(cache) = &cache {
+            std::fs::create_dir_all(cache).map_err(|err| {
+                crate::error::Error::PictureCache(cache.display().to_string(), err)
+            })?;
+        }
+        let download_dir = match &cache {
+            Some(cache) => cache.join("downloads"),
+            None => std::env::temp_dir().join("clock-app"),
+        };
+        if let Err(err) = std::fs::create_dir_all(&download_dir) {
+            log::warn!("failed to create {}: {}", download_dir.display(), err);
+        }
+
+        let unsplash = match &config.unsplash_query {
+            Some(query) => Some(crate::unsplash::Unsplash::new(
+                query.clone(),
+                config
+                    .unsplash_access_key
+                    .clone()
+                    .ok_or(crate::error::Error::UnsplashAccessKey)?,
+                width,
+                download_dir.join("unsplash"),
+                config.unsplash_cache_size,
+            )),
+            None => None,
+        };
+
+        let sources = match unsplash {
+            Some(_) => vec![Entry::new(Source::Unsplash)],
+            None if !is_url(path) && !std::path::Path::new(path).exists() => {
+                return Err(crate::error::Error::PicturePathNotFound(path.to_string()));
+            }
+            None => scan(path),
+        };
+        // manifest URL unreachable for now is fetched again later
+        let remote_manifest = unsplash.is_none()
+            && is_url(path)
+            && !matches!(sources.as_slice(), [entry] if entry.source == Source::Url(path.to_string()));
+        if sources.is_empty() && !remote_manifest {
+            log::warn!("no picture found in {}", path);
+            return Ok(None);
+        }
+        let sources = std::sync::Arc::new(std::sync::Mutex::new(sources));
+        let refresh = std::time::Duration::from_secs(config.picture_interval);
+        if remote_manifest {
+            refresh_manifest(path, std::sync::Arc::downgrade(&sources), refresh);
+        }
+        let watcher = if unsplash.is_some() || is_url(path) {
+            None
+        } else {
+            match watch(path, sources.clone()) {
+                Ok(watcher) => Some(watcher),
+                Err(err) => {
+                    log::error!("failed to watch {}: {}", path, err);
+                    None
+                }
+            }
+        };
+
+        // rendezvous channel keeps just one upcoming picture decoded at a time
+        let (sender, receiver) = std::sync::mpsc::sync_channel(0);
+        let weather = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let decoder_weather = weather.clone();
+        let timezone = config.timezone;
+        let loader = Loader {
+            width,
+            height,
+            cache,
+            download_dir,
+            cache_size: config.picture_cache_size * 1024 * 1024,
+            refresh,
+            caption: config.caption,
+        };
+        let (recall_sender, recall_receiver) = std::sync::mpsc::channel::<(u64, Recall)>();
+        let (recalled_sender, recalled) = std::sync::mpsc::channel();
+        let recall_loader = loader.clone();
+        std::thread::spawn(move || {
+            for (id, recall) in recall_receiver {
+                let picture = recall_loader.recall(&recall);
+                if recalled_sender.send((id, picture)).is_err() {
+                    break;
+                }
+            }
+        });
+        std::thread::spawn(move || {
+            let mut decoder = Decoder {
+                sources,
+                loader,
+                unsplash,
+                playlist: Playlist::new(order),
+                retries: Default::default(),
+                weather: decoder_weather,
+                timezone,
+            };
+            loop {
+                let picture = decoder.decode_next();
+                if sender.send(picture).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Some(Self {
+            receiver,
+            pending: None,
+            _watcher: watcher,
+            history: Default::default(),
+            position: 0,
+            decoded: Default::default(),
+            next_id: 0,
+            recall_sender,
+            recalled,
+            recalling: None,
+            weather,
+            timezone,
+        }))
+    }
+
+    /// Prefers pictures tagged with the condition from the next picture
+    pub fn set_weather(&self, condition: Option<crate::weather::Condition>) {
+        *self.weather.lock().unwrap() = condition;
+    }
+
+    /// Waits for the next picture being decoded up to the timeout
+    pub fn wait_next_picture(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> Option<crate::picture::Picture> {
+        if 0 < self.position {
+            return self.forward();
+        }
+        let (picture, recall, _) = self.upcoming(Some(timeout))?;
+        Some(self.push(picture, recall))
+    }
+
+    /// `None` also while the picture gone back or forward to is decoded again, which is
+    /// returned once it is ready
+    pub fn try_next_picture(&mut self) -> Option<crate::picture::Picture> {
+        if self.recalling.is_some() {
+            return self.recalled_picture();
+        }
+        if 0 < self.position {
+            return self.forward();
+        }
+        let (picture, recall, _) = self.upcoming(None)?;
+        Some(self.push(picture, recall))
+    }
+
+    /// Picture the next call of `try_next_picture` returns, `None` if it is not decoded yet or
+    /// comes from history
+    pub fn prefetch(&mut self) -> Option<&crate::picture::Picture> {
+        if 0 < self.position {
+            return None;
+        }
+        if self.pending.is_none() {
+            self.pending = self.upcoming(None);
+        }
+        self.pending.as_ref().map(|(picture, _, _)| picture)
+    }
+
+    /// Goes back to the picture shown before, `None` at the oldest one in history or until it
+    /// is decoded again
+    pub fn previous_picture(&mut self) -> Option<crate::picture::Picture> {
+        if self.history.len() <= self.position + 1 {
+            return None;
+        }
+        self.position += 1;
+        self.picture(self.history.len() - 1 - self.position)
+    }
+
+    /// Whether a picture of history is being decoded again, to be taken by `try_next_picture`
+    pub fn is_recalling(&self) -> bool {
+        self.recalling.is_some()
+    }
+
+    /// Picture shown last, `None` before the first one or until it is decoded again
+    pub fn current_picture(&mut self) -> Option<crate::picture::Picture> {
+        self.picture(self.history.len().checked_sub(1 + self.position)?)
+    }
+
+    /// Bytes of decoded pictures and encoded ones kept in history
+    pub fn memory(&self) -> u64 {
+        let decoded = self
+            .decoded
+            .iter()
+            .map(|(_, picture)| picture)
+            .chain(self.pending.as_ref().map(|(picture, _, _)| picture))
+            .flat_map(|picture| {
+                let frames = picture.frames.iter().map(|(image, _)| image);
+                frames.chain(picture.mipmaps.iter().flatten())
+            })
+            .map(|image| image.as_raw().len() as u64)
+            .sum::<u64>();
+        let encoded = self
+            .history
+            .iter()
+            .filter_map(|(_, recall)| recall.data.as_ref())
+            .map(|data| data.len() as u64)
+            .sum::<u64>();
+        decoded + encoded
+    }
+
+    /// Picture from decoder, dropping one picked for another part of the day or weather than now
+    /// as decoder picks ahead
+    fn upcoming(
+        &mut self,
+        timeout: Option<std::time::Duration>,
+    ) -> Option<(crate::picture::Picture, Recall, Option<Moment>)> {
+        let deadline = timeout.map(|timeout| std::time::Instant::now() + timeout);
+        loop {
+            let (picture, recall, moment) = match self.pending.take() {
+                Some(pending) => pending,
+                None => match deadline {
+                    Some(deadline) => self
+                        .receiver
+                        .recv_timeout(deadline.saturating_duration_since(std::time::Instant::now()))
+                        .ok()?,
+                    None => self.receiver.try_recv().ok()?,
+                },
+            };
+            if moment.is_none_or(|moment| moment == Moment::now(self.timezone, &self.weather)) {
+                return Some((picture, recall, moment));
+            }
+            log::debug!("drop picture {} picked for another moment", recall.source);
+        }
+    }
+
+    fn forward(&mut self) -> Option<crate::picture::Picture> {
+        if self.position == 0 {
+            return None;
+        }
+        self.position -= 1;
+        self.picture(self.history.len() - 1 - self.position)
+    }
+
+    fn push(
+        &mut self,
+        picture: crate::picture::Picture,
+        recall: Recall,
+    ) -> crate::picture::Picture {
+        if HISTORY_SIZE <= self.history.len() {
+            self.history.pop_front();
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.history.push_back((id, recall));
+        self.cache(id, picture.clone());
+        picture
+    }
+
+    /// Picture at the index of history if it is kept, otherwise sent to be decoded again
+    fn picture(&mut self, index: usize) -> Option<crate::picture::Picture> {
+        let (id, recall) = self.history.get(index)?.clone();
+        if let Some(position) = self.decoded.iter().position(|(other, _)| *other == id) {
+            let (_, picture) = self.decoded.remove(position).unwrap();
+            self.cache(id, picture.clone());
+            self.recalling = None;
+            return Some(picture);
+        }
+        if self.recalling != Some(id) {
+            log::debug!("decode picture {} again", recall.source);
+            self.recall_sender.send((id, recall)).ok()?;
+            self.recalling = Some(id);
+        }
+        None
+    }
+
+    fn recalled_picture(&mut self) -> Option<crate::picture::Picture> {
+        let target = self.recalling?;
+        while let Ok((id, picture)) = self.recalled.try_recv() {
+            if let Some(picture) = &picture {
+                self.cache(id, picture.clone());
+            }
+            if id == target {
+                self.recalling = None;
+                return picture;
+            }
+        }
+        None
+    }
+
+    fn cache(&mut self, id: u64, picture: crate::picture::Picture) {
+        if DECODED_SIZE <= self.decoded.len() {
+            self.decoded.pop_front();
+        }
+        self.decoded.push_back((id, picture));
+    }
+}
+
+/// What a shown picture is decoded again from, encoded data for sources giving a different
+/// picture every time
+#[derive(Clone)]
+struct Recall {
+    source: Source,
+    data: Option<std::sync::Arc<Vec<u8>>>,
+    duration: Option<std::time::Duration>,
+    transition: Option<std::time::Duration>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Source {
+    Path(std::path::PathBuf),
+    Url(String),
+    Unsplash,
+}
+
+/// Part of the day a picture is shown in, by local hour
+#[derive(Clone, Copy, PartialEq)]
+enum DayPeriod {
+    /// 5 to 10
+    Morning,
+    /// 10 to 17
+    Day,
+    /// 17 to 21
+    Evening,
+    /// 21 to 5
+    Night,
+}
+
+impl DayPeriod {
+    fn now(timezone: Option<chrono_tz::Tz>) -> Self {
+        use chrono::Timelike;
+
+        match crate::alarm::now(timezone).hour() {
+            5..=9 => Self::Morning,
+            10..=16 => Self::Day,
+            17..=20 => Self::Evening,
+            _ => Self::Night,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "morning" => Some(Self::Morning),
+            "day" => Some(Self::Day),
+            "evening" => Some(Self::Evening),
+            "night" => Some(Self::Night),
+            _ => None,
+        }
+    }
+}
+
+/// Source with its own display duration, transition, day period and weather, which are set in
+/// manifest (day period and weather also by subdirectory)
+#[derive(Clone, PartialEq)]
+struct Entry {
+    source: Source,
+    duration: Option<std::time::Duration>,
+    transition: Option<std::time::Duration>,
+    /// Shown only in this part of the day, any time if none
+    period: Option<DayPeriod>,
+    /// Shown only in this weather, any weather if none or weather is unknown
+    weather: Option<crate::weather::Condition>,
+}
+
+impl Entry {
+    fn new(source: Source) -> Self {
+        Self {
+            source,
+            duration: None,
+            transition: None,
+            period: None,
+            weather: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Url(url) => write!(f, "{}", url),
+            Self::Unsplash => write!(f, "unsplash"),
+        }
+    }
+}
+
+/// Part of the day and weather pictures are picked for
+#[derive(Clone, Copy, PartialEq)]
+struct Moment {
+    period: DayPeriod,
+    weather: Option<crate::weather::Condition>,
+}
+
+impl Moment {
+    fn now(
+        timezone: Option<chrono_tz::Tz>,
+        weather: &std::sync::Mutex<Option<crate::weather::Condition>>,
+    ) -> Self {
+        Self {
+            period: DayPeriod::now(timezone),
+            weather: *weather.lock().unwrap(),
+        }
+    }
+}
+
+struct Decoder {
+    sources: std::sync::Arc<std::sync::Mutex<Vec<Entry>>>,
+    loader: Loader,
+    unsplash: Option<crate::unsplash::Unsplash>,
+    playlist: Playlist,
+    /// Remote sources failed to load, with the wait before the next try and its end
+    retries: std::collections::HashMap<Source, (std::time::Duration, std::time::Instant)>,
+    weather: std::sync::Arc<std::sync::Mutex<Option<crate::weather::Condition>>>,
+    timezone: Option<chrono_tz::Tz>,
+}
+
+impl Decoder {
+    /// Next picture with the moment it is picked for, `None` if no source is tagged with one
+    fn decode_next(&mut self) -> (crate::picture::Picture, Recall, Option<Moment>) {
+        loop {
+            let now = std::time::Instant::now();
+            let sources = self
+                .sources
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|entry| {
+                    self.retries
+                        .get(&entry.source)
+                        .is_none_or(|(_, retry_instant)| *retry_instant <= now)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            let moment = Moment::now(self.timezone, &self.weather);
+            let tagged = sources
+                .iter()
+                .any(|entry| entry.period.is_some() || entry.weather.is_some());
+            let Some(entry) = self.playlist.next(&sources, moment) else {
+                if self.retries.is_empty() {
+                    log::warn!("no picture found, wait for new pictures");
+                } else {
+                    log::debug!("wait to retry failed pictures");
+                }
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                continue;
+            };
+            log::debug!("load picture {}", entry.source);
+            let instant = std::time::Instant::now();
+            match self.load(&entry.source) {
+                Ok((picture, data)) => {
+                    crate::metrics::record_picture_load(instant.elapsed());
+                    self.retries.remove(&entry.source);
+                    let picture = crate::picture::Picture {
+                        duration: entry.duration,
+                        transition: entry.transition,
+                        ..picture
+                    };
+                    // unsplash gives another photo on the next read
+                    let recall = Recall {
+                        data: (entry.source == Source::Unsplash).then(|| std::sync::Arc::new(data)),
+                        source: entry.source,
+                        duration: entry.duration,
+                        transition: entry.transition,
+                    };
+                    return (picture, recall, tagged.then_some(moment));
+                }
+                // remote sources may be back later, while broken files stay broken
+                Err(err) => match &entry.source {
+                    Source::Path(_) => {
+                        log::warn!("failed to load picture {}: {}", entry.source, err);
+                        self.sources.lock().unwrap().retain(|other| other != &entry);
+                    }
+                    _ => {
+                        let wait = self
+                            .retries
+                            .get(&entry.source)
+                            .map_or(RETRY_MIN, |(wait, _)| (*wait * 2).min(RETRY_MAX));
+                        log::warn!(
+                            "failed to load picture {}: {}, retry in {:?}",
+                            entry.source,
+                            err,
+                            wait
+                        );
+                        self.retries.insert(entry.source, (wait, now + wait));
+                    }
+                },
+            }
+        }
+    }
+
+    /// Picture with its encoded data
+    fn load(
+        &self,
+        source: &Source,
+    ) -> Result<(crate::picture::Picture, Vec<u8>), Box<dyn std::error::Error>> {
+        let data = match source {
+            Source::Unsplash => match &self.unsplash {
+                Some(unsplash) => unsplash.fetch()?,
+                None => return Err("unsplash is not configured".into()),
+            },
+            _ => self.loader.read(source)?,
+        };
+        let picture = self.loader.decode(source, &data)?;
+        Ok((picture, data))
+    }
+}
+
+/// Reads and decodes pictures shrunk to the picture size, through the cache
+#[derive(Clone)]
+struct Loader {
+    width: u32,
+    height: u32,
+    cache: Option<std::path::PathBuf>,
+    download_dir: std::path::PathBuf,
+    /// Bytes of files kept in cache and in download directory each
+    cache_size: u64,
+    /// Age of downloads fetched again
+    refresh: std::time::Duration,
+    caption: bool,
+}
+
+impl Loader {
+    fn decode(
+        &self,
+        source: &Source,
+        data: &[u8],
+    ) -> Result<crate::picture::Picture, Box<dyn std::error::Error>> {
+        let caption = self.caption.then(|| caption(source, data)).flatten();
+
+        // unsplash photos are rotated out, so they are not cached
+        let cache = self.cache.as_ref().filter(|_| *source != Source::Unsplash);
+        let cache_path = cache.map(|cache| {
+            cache.join(format!(
+                "{:016x}_{}x{}.rgba",
+                fnv1a(data),
+                self.width,
+                self.height
+            ))
+        });
+        if let Some(image) = cache_path.as_deref().and_then(read_cache) {
+            log::debug!("hit picture cache");
+            let mipmaps = vec![crate::picture::mipmap(&image)];
+            let frames = vec![(image, std::time::Duration::MAX)];
+            return Ok(crate::picture::Picture {
+                name: source.to_string(),
+                serial: SERIAL.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+                caption,
+                frames,
+                mipmaps,
+                duration: None,
+                transition: None,
+            });
+        }
+
+        let frames = decode(data)?
+            .into_iter()
+            .map(|(image, delay)| (self.shrink(image), delay))
+            .collect::<Vec<_>>();
+
+        // animated pictures are not cached
+        if let ([(image, _)], Some(cache_path)) = (frames.as_slice(), &cache_path) {
+            if let Err(err) = write_cache(cache_path, image) {
+                log::warn!(
+                    "failed to write picture cache {}: {}",
+                    cache_path.display(),
+                    err
+                );
+            }
+            prune(cache.unwrap(), self.cache_size);
+        }
+        let mipmaps = frames
+            .iter()
+            .map(|(image, _)| crate::picture::mipmap(image))
+            .collect();
+        Ok(crate::picture::Picture {
+            name: source.to_string(),
+            serial: SERIAL.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            caption,
+            frames,
+            mipmaps,
+            duration: None,
+            transition: None,
+        })
+    }
+
+    /// Picture of history decoded again, from the kept data or the source
+    fn recall(&self, recall: &Recall) -> Option<crate::picture::Picture> {
+        let picture = match &recall.data {
+            Some(data) => self.decode(&recall.source, data),
+            None => self
+                .read(&recall.source)
+                .and_then(|data| self.decode(&recall.source, &data)),
+        };
+        match picture {
+            Ok(picture) => Some(crate::picture::Picture {
+                duration: recall.duration,
+                transition: recall.transition,
+                ..picture
+            }),
+            Err(err) => {
+                log::warn!("failed to load picture {}: {}", recall.source, err);
+                None
+            }
+        }
+    }
+
+    fn read(&self, source: &Source) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        use std::io::Read;
+
+        match source {
+            Source::Path(path) => Ok(std::fs::read(path)?),
+            Source::Url(url) => {
+                let download_path = self
+                    .download_dir
+                    .join(format!("{:016x}", fnv1a(url.as_bytes())));
+                let fresh = std::fs::metadata(&download_path)
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| {
+                        modified
+                            .elapsed()
+                            .is_ok_and(|elapsed| elapsed < self.refresh)
+                    });
+                if fresh {
+                    if let Ok(data) = std::fs::read(&download_path) {
+                        return Ok(data);
+                    }
+                }
+
+                log::debug!("download {}", url);
+                let mut data = vec![];
+                let downloaded = ureq::get(url)
+                    .call()
+                    .map_err(Box::<dyn std::error::Error>::from)
+                    .and_then(|response| Ok(response.into_reader().read_to_end(&mut data)?));
+                if let Err(err) = downloaded {
+                    // outdated download is better than none while offline
+                    return match std::fs::read(&download_path) {
+                        Ok(data) => {
+                            log::warn!("failed to download {}: {}, use the last one", url, err);
+                            Ok(data)
+                        }
+                        Err(_) => Err(err),
+                    };
+                }
+                if let Err(err) = std::fs::write(&download_path, &data) {
+                    log::warn!("failed to write {}: {}", download_path.display(), err);
+                }
+                prune(&self.download_dir, self.cache_size);
+                Ok(data)
+            }
+            Source::Unsplash => Err("unsplash photo cannot be read again".into()),
+        }
+    }
+
+    fn shrink(&self, image: image::RgbaImage) -> image::RgbaImage {
+        // shrink only down to the size covering picture size, scaling is done by GPU
+        let scale = f32::max(
+            self.width as f32 / image.width() as f32,
+            self.height as f32 / image.height() as f32,
+        );
+        if scale < 1.0 {
+            let width = (image.width() as f32 * scale).ceil() as u32;
+            let height = (image.height() as f32 * scale).ceil() as u32;
+            image::imageops::thumbnail(&image, width, height)
+        } else {
+            image
+        }
+    }
+}
+
+/// Picture of the URL decoded from data fetched elsewhere, as by the browser, shrunk to cover the
+/// size
+#[cfg(target_arch = "wasm32")]
+pub fn decode_fetched(
+    url: &str,
+    data: &[u8],
+    (width, height): (u32, u32),
+    caption: bool,
+) -> Result<crate::picture::Picture, Box<dyn std::error::Error>> {
+    let loader = Loader {
+        width,
+        height,
+        cache: None,
+        download_dir: std::path::PathBuf::new(),
+        cache_size: 0,
+        refresh: std::time::Duration::ZERO,
+        caption,
+    };
+    loader.decode(&Source::Url(url.to_string()), data)
+}
+
+/// Hands out sources in the configured order, showing every source once before repeating
+/// (except for plain shuffle)
+struct Playlist {
+    order: PictureOrder,
+    queue: std::collections::VecDeque<Entry>,
+    last: Option<Entry>,
+    rng: rand::rngs::ThreadRng,
+}
+
+impl Playlist {
+    fn new(order: PictureOrder) -> Self {
+        Self {
+            order,
+            queue: Default::default(),
+            last: None,
+            rng: rand::thread_rng(),
+        }
+    }
+
+    fn next(&mut self, sources: &[Entry], moment: Moment) -> Option<Entry> {
+        use rand::seq::SliceRandom;
+
+        // pictures of other parts of the day or weather are skipped unless nothing else is left
+        let Moment { period, weather } = moment;
+        let timely = sources
+            .iter()
+            .filter(|entry| entry.period.is_none_or(|other| other == period))
+            .filter(|entry| {
+                entry
+                    .weather
+                    .is_none_or(|other| weather.is_none_or(|weather| weather == other))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let sources = if timely.is_empty() { sources } else { &timely };
+
+        if let PictureOrder::Shuffle = self.order {
+            return sources.choose(&mut self.rng).cloned();
+        }
+
+        // sources may be rescanned meanwhile, removed ones are dropped and new ones wait for the next round
+        self.queue.retain(|source| sources.contains(source));
+        if self.queue.is_empty() {
+            let mut round = sources.to_vec();
+            if let PictureOrder::ShuffleNoRepeat = self.order {
+                round.shuffle(&mut self.rng);
+                // avoid showing the same picture twice in a row across rounds
+                if round.len() > 1 && round.first() == self.last.as_ref() {
+                    let len = round.len();
+                    round.swap(0, len - 1);
+                }
+            }
+            self.queue = round.into();
+        }
+
+        let source = self.queue.pop_front()?;
+        self.last = Some(source.clone());
+        Some(source)
+    }
+}
+
+fn decode(
+    data: &[u8],
+) -> Result<Vec<(image::RgbaImage, std::time::Duration)>, Box<dyn std::error::Error>> {
+    use image::AnimationDecoder;
+
+    let frames = match image::guess_format(data)? {
+        image::ImageFormat::Gif => {
+            let decoder = image::codecs::gif::GifDecoder::new(std::io::Cursor::new(data))?;
+            decoder.into_frames().collect_frames()?
+        }
+        image::ImageFormat::Png => {
+            let decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(data))?;
+            if decoder.is_apng() {
+                decoder.apng().into_frames().collect_frames()?
+            } else {
+                vec![]
+            }
+        }
+        _ => vec![],
+    };
+    if frames.len() <= 1 {
+        let image = image::load_from_memory(data)?.to_rgba8();
+        let image = orient(image, orientation(data));
+        return Ok(vec![(image, std::time::Duration::MAX)]);
+    }
+
+    Ok(frames
+        .into_iter()
+        .map(|frame| {
+            let (numer, denom) = frame.delay().numer_denom_ms();
+            // browsers treat too short delays as 100ms
+            let delay = match numer / denom.max(1) {
+                0..=10 => std::time::Duration::from_millis(100),
+                delay => std::time::Duration::from_millis(delay as u64),
+            };
+            (frame.into_buffer(), delay)
+        })
+        .collect())
+}
+
+/// Reads EXIF orientation tag (1 to 8), 1 (upright) when missing
+fn orientation(data: &[u8]) -> u32 {
+    exif::Reader::new()
+        .read_from_container(&mut std::io::Cursor::new(data))
+        .ok()
+        .and_then(|exif| {
+            exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+        })
+        .unwrap_or(1)
+}
+
+/// Title from sidecar `.txt`, XMP, IPTC or EXIF, followed by EXIF date taken
+fn caption(source: &Source, data: &[u8]) -> Option<String> {
+    let exif = exif::Reader::new()
+        .read_from_container(&mut std::io::Cursor::new(data))
+        .ok();
+    let exif_text = |tag| {
+        let field = exif.as_ref()?.get_field(tag, exif::In::PRIMARY)?;
+        match &field.value {
+            exif::Value::Ascii(values) => values
+                .first()
+                .map(|value| String::from_utf8_lossy(value).trim().to_string())
+                .filter(|value| !value.is_empty()),
+            _ => None,
+        }
+    };
+
+    let sidecar = match source {
+        Source::Path(path) => std::fs::read_to_string(path.with_extension("txt"))
+            .ok()
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty()),
+        _ => None,
+    };
+    let title = sidecar
+        .or_else(|| xmp_title(data))
+        .or_else(|| iptc_title(data))
+        .or_else(|| exif_text(exif::Tag::ImageDescription));
+    let date = exif_text(exif::Tag::DateTimeOriginal).and_then(|date| {
+        chrono::NaiveDateTime::parse_from_str(&date, "%Y:%m:%d %H:%M:%S")
+            .ok()
+            .map(|date| date.format("%Y-%m-%d").to_string())
+    });
+    match (title, date) {
+        (Some(title), Some(date)) => Some(format!("{}, {}", title, date)),
+        (title, date) => title.or(date),
+    }
+}
+
+/// Reads the first `dc:title` entry of XMP packet
+fn xmp_title(data: &[u8]) -> Option<String> {
+    let start = find(data, b"<dc:title>")?;
+    let data = &data[start..];
+    let data = &data[..find(data, b"</dc:title>")?];
+    let data = &data[find(data, b"<rdf:li")?..];
+    let data = &data[find(data, b">")? + 1..];
+    let data = &data[..find(data, b"</rdf:li>")?];
+    let title = String::from_utf8_lossy(data)
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'");
+    Some(title.trim().to_string()).filter(|title| !title.is_empty())
+}
+
+/// Reads caption or object name dataset of IPTC block in JPEG Photoshop segment
+fn iptc_title(data: &[u8]) -> Option<String> {
+    let start = find(data, b"Photoshop 3.0\0")?;
+    let data = &data[start..data.len().min(start + 0x10000)];
+    [0x78, 0x05].into_iter().find_map(|dataset| {
+        let position = find(data, &[0x1c, 0x02, dataset])? + 3;
+        let length = u16::from_be_bytes([*data.get(position)?, *data.get(position + 1)?]);
+        let value = data.get(position + 2..position + 2 + length as usize)?;
+        let title = String::from_utf8_lossy(value).trim().to_string();
+        Some(title).filter(|title| !title.is_empty())
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn orient(image: image::RgbaImage, orientation: u32) -> image::RgbaImage {
+    use image::imageops;
+
+    match orientation {
+        2 => imageops::flip_horizontal(&image),
+        3 => imageops::rotate180(&image),
+        4 => imageops::flip_vertical(&image),
+        5 => imageops::flip_horizontal(&imageops::rotate90(&image)),
+        6 => imageops::rotate90(&image),
+        7 => imageops::flip_horizontal(&imageops::rotate270(&image)),
+        8 => imageops::rotate270(&image),
+        _ => image,
+    }
+}
+
+fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+fn is_picture(path: &str) -> bool {
+    let path = path.to_ascii_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif"]
+        .iter()
+        .any(|extension| path.ends_with(extension))
+}
+
+/// Collects pictures from a directory, a single picture URL, or a manifest (file or URL)
+fn scan(path: &str) -> Vec<Entry> {
+    log::debug!("scan pictures");
+    if is_url(path) {
+        if is_picture(path) {
+            return vec![Entry::new(Source::Url(path.to_string()))];
+        }
+        use std::io::Read;
+
+        let source = Source::Url(path.to_string());
+        let entries = ureq::get(path)
+            .call()
+            .map_err(Box::<dyn std::error::Error>::from)
+            .and_then(|response| {
+                // URLs with a query or without an extension are told apart by content type
+                if response.content_type().starts_with("image/") {
+                    return Ok(vec![Entry::new(source)]);
+                }
+                let mut data = vec![];
+                response.into_reader().read_to_end(&mut data)?;
+                entries_of(data, source, None)
+            });
+        return match entries {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::error!("failed to read {}: {}", path, err);
+                vec![]
+            }
+        };
+    }
+
+    let path = std::path::Path::new(path);
+    if path.is_file() {
+        let entries = std::fs::read(path)
+            .map_err(Box::<dyn std::error::Error>::from)
+            .and_then(|data| entries_of(data, Source::Path(path.to_path_buf()), path.parent()));
+        return match entries {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::error!("failed to read {}: {}", path.display(), err);
+                vec![]
+            }
+        };
+    }
+
+    // subdirectories named after parts of the day or weather hold pictures shown only then,
+    // other files such as sidecar captions are skipped
+    let is_picture_file =
+        |path: &std::path::PathBuf| !path.is_dir() && path.to_str().is_some_and(is_picture);
+    let mut sources = vec![];
+    for path in list_dir(path) {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        let period = DayPeriod::from_name(name);
+        let weather = crate::weather::Condition::from_name(name);
+        match (period, weather) {
+            _ if is_picture_file(&path) => sources.push(Entry::new(Source::Path(path))),
+            (None, None) => {}
+            _ if path.is_dir() => {
+                sources.extend(
+                    list_dir(&path)
+                        .into_iter()
+                        .filter(is_picture_file)
+                        .map(|path| Entry {
+                            period,
+                            weather,
+                            ..Entry::new(Source::Path(path))
+                        }),
+                );
+            }
+            _ => {}
+        }
+    }
+    sources
+}
+
+/// The source alone if the data is a picture, or else sources listed in the data as manifest
+fn entries_of(
+    data: Vec<u8>,
+    source: Source,
+    base: Option<&std::path::Path>,
+) -> Result<Vec<Entry>, Box<dyn std::error::Error>> {
+    if image::guess_format(&data).is_ok_and(|format| format.can_read()) {
+        return Ok(vec![Entry::new(source)]);
+    }
+    Ok(parse_manifest(&String::from_utf8(data)?, base))
+}
+
+/// Sorted paths in a directory except hidden ones
+fn list_dir(path: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            log::error!("failed to read {}: {}", path.display(), err);
+            return vec![];
+        }
+    };
+    let mut paths = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| !name.starts_with('.'))
+        })
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+/// Manifest lists picture URLs or paths (relative to the manifest) line by line, each
+/// optionally followed by `duration=<s>` and `transition=<ms>` overriding the options and
+/// `period=<morning|day|evening|night>` and `weather=<sun|clouds|rain|snow>`
+fn parse_manifest(text: &str, dir: Option<&std::path::Path>) -> Vec<Entry> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut line = line;
+            let mut duration = None;
+            let mut transition = None;
+            let mut period = None;
+            let mut weather = None;
+            // settings are taken from the end, so paths may contain spaces
+            while let Some((rest, setting)) = line.rsplit_once(char::is_whitespace) {
+                match setting.split_once('=') {
+                    Some(("duration", value)) if value.parse::<u64>().is_ok() => {
+                        duration = Some(std::time::Duration::from_secs(value.parse().unwrap()));
+                    }
+                    Some(("transition", value)) if value.parse::<u64>().is_ok() => {
+                        transition = Some(std::time::Duration::from_millis(value.parse().unwrap()));
+                    }
+                    Some(("period", value)) if DayPeriod::from_name(value).is_some() => {
+                        period = DayPeriod::from_name(value);
+                    }
+                    Some(("weather", value))
+                        if crate::weather::Condition::from_name(value).is_some() =>
+                    {
+                        weather = crate::weather::Condition::from_name(value);
+                    }
+                    _ => break,
+                }
+                line = rest.trim_end();
+            }
+            let source = match (is_url(line), dir) {
+                (true, _) => Source::Url(line.to_string()),
+                (false, Some(dir)) => Source::Path(dir.join(line)),
+                (false, None) => Source::Path(line.into()),
+            };
+            Entry {
+                source,
+                duration,
+                transition,
+                period,
+                weather,
+            }
+        })
+        .collect()
+}
+
+/// Fetches manifest URL again every interval, or sooner while it fails, until the sources are
+/// dropped
+fn refresh_manifest(
+    path: &str,
+    sources: std::sync::Weak<std::sync::Mutex<Vec<Entry>>>,
+    interval: std::time::Duration,
+) {
+    let path = path.to_string();
+    let mut retry = sources
+        .upgrade()
+        .filter(|sources| sources.lock().unwrap().is_empty())
+        .map(|_| RETRY_MIN);
+    std::thread::spawn(move || loop {
+        std::thread::sleep(retry.unwrap_or(interval));
+        let Some(sources) = sources.upgrade() else {
+            break;
+        };
+        // sources of the last manifest are kept while it cannot be read
+        let entries = scan(&path);
+        if entries.is_empty() {
+            retry = Some(retry.map_or(RETRY_MIN, |retry| (retry * 2).min(RETRY_MAX)));
+        } else {
+            *sources.lock().unwrap() = entries;
+            retry = None;
+        }
+    });
+}
+
+fn watch(
+    path: &str,
+    sources: std::sync::Arc<std::sync::Mutex<Vec<Entry>>>,
+) -> notify::Result<notify::RecommendedWatcher> {
+    use notify::Watcher;
+
+    let picture_path = path.to_string();
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+            Ok(event) if !event.kind.is_access() => {
+                *sources.lock().unwrap() = scan(&picture_path);
+            }
+            Ok(_) => {}
+            Err(err) => log::error!("failed to watch pictures: {}", err),
+        })?;
+    watcher.watch(std::path::Path::new(path), notify::RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+/// Removes the oldest files in the directory beyond the size, subdirectories are left alone
+fn prune(dir: &std::path::Path, size: u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry
+                .metadata()
+                .ok()
+                .filter(|metadata| metadata.is_file())?;
+            Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by_key(|(_, _, modified)| std::cmp::Reverse(*modified));
+    let mut total = 0;
+    for (path, len, _) in files {
+        total += len;
+        if size < total {
+            log::debug!("remove {} from cache", path.display());
+            if let Err(err) = std::fs::remove_file(&path) {
+                log::warn!("failed to remove {}: {}", path.display(), err);
+            }
+        }
+    }
+}
+
+/// Cache file consists of width and height (u32, little endian) followed by RGBA pixels
+fn read_cache(path: &std::path::Path) -> Option<image::RgbaImage> {
+    let data = std::fs::read(path).ok()?;
+    let width = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+    let height = u32::from_le_bytes(data.get(4..8)?.try_into().ok()?);
+    image::RgbaImage::from_raw(width, height, data[8..].to_vec())
+}
+
+fn write_cache(path: &std::path::Path, picture: &image::RgbaImage) -> std::io::Result<()> {
+    let mut data = Vec::with_capacity(8 + picture.len());
+    data.extend(picture.width().to_le_bytes());
+    data.extend(picture.height().to_le_bytes());
+    data.extend(picture.as_raw());
+    std::fs::write(path, data)
+}
+
+/// 64-bit FNV-1a, stable across builds unlike std hasher
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_settings_are_parsed() {
+        let text = "\
+# comment
+a.png
+my photo.jpg duration=30 transition=500
+https://example.com/b.jpg period=night weather=rain
+c.png duration=soon
+";
+        let entries = parse_manifest(text, Some(std::path::Path::new("/pictures")));
+        assert_eq!(entries.len(), 4);
+        assert!(entries[0] == Entry::new(Source::Path("/pictures/a.png".into())));
+        assert!(
+            entries[1]
+                == Entry {
+                    duration: Some(std::time::Duration::from_secs(30)),
+                    transition: Some(std::time::Duration::from_millis(500)),
+                    ..Entry::new(Source::Path("/pictures/my photo.jpg".into()))
+                }
+        );
+        assert!(
+            entries[2]
+                == Entry {
+                    period: Some(DayPeriod::Night),
+                    weather: Some(crate::weather::Condition::Rain),
+                    ..Entry::new(Source::Url("https://example.com/b.jpg".into()))
+                }
+        );
+        // invalid settings are taken as part of the path
+        assert!(entries[3] == Entry::new(Source::Path("/pictures/c.png duration=soon".into())));
+    }
+}
@@ -0,0 +1,61 @@
+/// Face, colors and pictures switched together by schedule, key or remote control
+#[derive(Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub name: String,
+    /// Daily period the profile is used in unless another one is chosen
+    pub schedule: Option<crate::night::NightSchedule>,
+    pub face: Option<crate::face::Face>,
+    pub text_color: Option<crate::text::Color>,
+    pub greeting: Option<String>,
+    pub picture_path: Option<String>,
+    pub picture_interval: Option<u64>,
+}
+
+/// Index of the chosen profile, or of the first one scheduled now if none is chosen
+pub fn active(config: &crate::config::Config, chosen: Option<&str>) -> Option<usize> {
+    if let Some(name) = chosen {
+        return config
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name);
+    }
+    let time = crate::alarm::now(config.timezone).time();
+    config.profiles.iter().position(|profile| {
+        profile
+            .schedule
+            .is_some_and(|schedule| schedule.contains(time))
+    })
+}
+
+/// Name of the profile following the given one, wrapping around to the first
+pub fn next(config: &crate::config::Config, profile: Option<usize>) -> Option<String> {
+    let next = profile.map_or(0, |profile| (profile + 1) % config.profiles.len());
+    config
+        .profiles
+        .get(next)
+        .map(|profile| profile.name.clone())
+}
+
+/// Config with settings of the profile overridden
+pub fn apply(config: &crate::config::Config, profile: Option<usize>) -> crate::config::Config {
+    let mut config = config.clone();
+    if let Some(profile) = profile.map(|profile| config.profiles[profile].clone()) {
+        if let Some(face) = profile.face {
+            config.face = face;
+        }
+        if let Some(text_color) = profile.text_color {
+            config.text_color = text_color;
+        }
+        if let Some(greeting) = profile.greeting {
+            config.greeting = Some(greeting);
+        }
+        if let Some(picture_path) = profile.picture_path {
+            config.picture_path = picture_path;
+        }
+        if let Some(picture_interval) = profile.picture_interval {
+            config.picture_interval = picture_interval;
+        }
+    }
+    config
+}
@@ -0,0 +1,148 @@
+/// Floats of an instance: rect, shape and color
+const INSTANCE_LEN: usize = 12;
+
+/// Rounded rectangles with optional glow, which procedural clock faces are built of
+pub struct QuadPipeline {
+    pipeline: wgpu::RenderPipeline,
+    instances: Vec<[f32; INSTANCE_LEN]>,
+}
+
+impl QuadPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let shader =
+            device.create_shader_module(wgpu::include_wgsl!("../assets/shaders/quad.wgsl"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; INSTANCE_LEN]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x4,
+                        1 => Float32x4,
+                        2 => Float32x4,
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            instances: vec![],
+        }
+    }
+
+    /// Queues rectangle given as left, top, right, bottom [px], with corner radius and glow
+    /// radius [px], in linear color
+    pub fn queue(
+        &mut self,
+        [left, top, right, bottom]: [f32; 4],
+        radius: f32,
+        glow: f32,
+        color: [f32; 4],
+        (target_width, target_height): (u32, u32),
+    ) {
+        let to_clip = |x: f32, y: f32| {
+            (
+                x / target_width as f32 * 2.0 - 1.0,
+                1.0 - y / target_height as f32 * 2.0,
+            )
+        };
+        let (clip_left, clip_top) = to_clip(left - glow, top - glow);
+        let (clip_right, clip_bottom) = to_clip(right + glow, bottom + glow);
+        let [r, g, b, a] = color;
+        self.instances.push([
+            clip_left,
+            clip_top,
+            clip_right,
+            clip_bottom,
+            (right - left) * 0.5,
+            (bottom - top) * 0.5,
+            radius,
+            glow,
+            r,
+            g,
+            b,
+            a,
+        ]);
+    }
+
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        use wgpu::util::DeviceExt;
+
+        let instances = std::mem::take(&mut self.instances);
+        if instances.is_empty() {
+            return;
+        }
+
+        let data = instances
+            .iter()
+            .flatten()
+            .flat_map(|c| c.to_ne_bytes())
+            .collect::<Vec<_>>();
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &data,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_vertex_buffer(0, instance_buffer.slice(..));
+        pass.draw(0..4, 0..instances.len() as u32);
+    }
+}
@@ -0,0 +1,173 @@
+/// Background drawn by a fragment shader instead of pictures
+pub struct ShaderPipeline {
+    target_width: u32,
+    target_height: u32,
+    start_instant: web_time::Instant,
+    animated: bool,
+    tint: [f32; 3],
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl ShaderPipeline {
+    /// Compiles WGSL source defining `main_image`, failing on shader errors.
+    /// Shader not animated is redrawn only at update interval.
+    pub async fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+        target_width: u32,
+        target_height: u32,
+        source: &str,
+        animated: bool,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: std::mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        // errors of user shader are caught instead of panicking
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                format!(
+                    "{}\n{}",
+                    include_str!("../assets/shaders/background.wgsl"),
+                    source
+                )
+                .into(),
+            ),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+        if let Some(err) = device.pop_error_scope().await {
+            return Err(err.into());
+        }
+
+        Ok(Self {
+            target_width,
+            target_height,
+            start_instant: web_time::Instant::now(),
+            animated,
+            tint: [1.0; 3],
+            uniform_buffer,
+            bind_group,
+            pipeline,
+        })
+    }
+
+    pub fn resize(&mut self, target_width: u32, target_height: u32) {
+        self.target_width = target_width;
+        self.target_height = target_height;
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.animated
+    }
+
+    pub fn set_tint(&mut self, tint: [f32; 3]) {
+        self.tint = tint;
+    }
+
+    pub fn update(&mut self, queue: &wgpu::Queue) {
+        let uniform = [
+            self.target_width as f32,
+            self.target_height as f32,
+            self.start_instant.elapsed().as_secs_f32(),
+            0.0,
+            self.tint[0],
+            self.tint[1],
+            self.tint[2],
+            0.0,
+        ];
+        let data = uniform
+            .iter()
+            .flat_map(|c| c.to_ne_bytes())
+            .collect::<Vec<_>>();
+        queue.write_buffer(&self.uniform_buffer, 0, &data);
+    }
+
+    pub fn draw(
+        &self,
+        _device: &wgpu::Device,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
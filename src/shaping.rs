@@ -0,0 +1,175 @@
+/// Layout shaping texts by rustybuzz, which places glyphs of complex scripts (e.g. Arabic,
+/// Devanagari) and right-to-left runs that the built-in layout of glyph brush draws broken
+#[derive(Clone)]
+pub struct ShapedLayout {
+    /// Data of fonts in order of font id
+    fonts: std::sync::Arc<Vec<Vec<u8>>>,
+    h_align: wgpu_glyph::HorizontalAlign,
+    v_align: wgpu_glyph::VerticalAlign,
+}
+
+impl ShapedLayout {
+    pub fn new(fonts: Vec<Vec<u8>>) -> Self {
+        Self {
+            fonts: std::sync::Arc::new(fonts),
+            h_align: wgpu_glyph::HorizontalAlign::Left,
+            v_align: wgpu_glyph::VerticalAlign::Top,
+        }
+    }
+
+    pub fn with_align(
+        &self,
+        h_align: wgpu_glyph::HorizontalAlign,
+        v_align: wgpu_glyph::VerticalAlign,
+    ) -> Self {
+        Self {
+            fonts: self.fonts.clone(),
+            h_align,
+            v_align,
+        }
+    }
+}
+
+// fonts never change in a layout, so only alignment tells layouts apart
+impl std::hash::Hash for ShapedLayout {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.h_align.hash(state);
+        self.v_align.hash(state);
+    }
+}
+
+struct Line {
+    glyphs: Vec<wgpu_glyph::SectionGlyph>,
+    width: f32,
+    ascent: f32,
+    height: f32,
+}
+
+impl wgpu_glyph::GlyphPositioner for ShapedLayout {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &wgpu_glyph::SectionGeometry,
+        sections: &[S],
+    ) -> Vec<wgpu_glyph::SectionGlyph>
+    where
+        F: wgpu_glyph::ab_glyph::Font,
+        S: glyph_brush_layout::ToSectionText,
+    {
+        use wgpu_glyph::ab_glyph::ScaleFont;
+
+        let faces = self
+            .fonts
+            .iter()
+            .map(|data| rustybuzz::Face::from_slice(data, 0))
+            .collect::<Vec<_>>();
+
+        let new_line = || Line {
+            glyphs: vec![],
+            width: 0.0,
+            ascent: 0.0,
+            height: 0.0,
+        };
+        let mut lines = vec![new_line()];
+        for (section_index, section) in sections.iter().enumerate() {
+            let section = section.to_section_text();
+            let font = fonts[section.font_id.0].as_scaled(section.scale);
+            let Some(Some(face)) = faces.get(section.font_id.0) else {
+                continue;
+            };
+
+            let mut start = 0;
+            for (i, segment) in section.text.split('\n').enumerate() {
+                if 0 < i {
+                    lines.push(new_line());
+                }
+                let line = lines.last_mut().unwrap();
+                line.ascent = line.ascent.max(font.ascent());
+                line.height = line
+                    .height
+                    .max(font.ascent() - font.descent() + font.line_gap());
+
+                if segment.is_empty() {
+                    start += 1;
+                    continue;
+                }
+                let bidi = unicode_bidi::ParagraphBidiInfo::new(segment, None);
+                let (levels, runs) = bidi.visual_runs(0..segment.len());
+                for run in runs {
+                    let mut buffer = rustybuzz::UnicodeBuffer::new();
+                    buffer.push_str(&segment[run.clone()]);
+                    buffer.guess_segment_properties();
+                    buffer.set_direction(if levels[run.start].is_rtl() {
+                        rustybuzz::Direction::RightToLeft
+                    } else {
+                        rustybuzz::Direction::LeftToRight
+                    });
+                    let output = rustybuzz::shape(face, &[], buffer);
+
+                    for (info, position) in
+                        output.glyph_infos().iter().zip(output.glyph_positions())
+                    {
+                        let x = geometry.screen_position.0
+                            + line.width
+                            + position.x_offset as f32 * font.h_scale_factor();
+                        let y = -position.y_offset as f32 * font.v_scale_factor();
+                        line.glyphs.push(wgpu_glyph::SectionGlyph {
+                            section_index,
+                            byte_index: start + run.start + info.cluster as usize,
+                            glyph: wgpu_glyph::ab_glyph::GlyphId(info.glyph_id as u16)
+                                .with_scale_and_position(
+                                    section.scale,
+                                    wgpu_glyph::ab_glyph::point(x, y),
+                                ),
+                            font_id: section.font_id,
+                        });
+                        line.width += position.x_advance as f32 * font.h_scale_factor();
+                    }
+                }
+                start += segment.len() + 1;
+            }
+        }
+
+        // lines are aligned one by one, and placed from the top of the whole text
+        let total_height = lines.iter().map(|line| line.height).sum::<f32>();
+        let mut top = match self.v_align {
+            wgpu_glyph::VerticalAlign::Top => geometry.screen_position.1,
+            wgpu_glyph::VerticalAlign::Center => geometry.screen_position.1 - total_height * 0.5,
+            wgpu_glyph::VerticalAlign::Bottom => geometry.screen_position.1 - total_height,
+        };
+        let mut glyphs = vec![];
+        for line in lines {
+            let shift = match self.h_align {
+                wgpu_glyph::HorizontalAlign::Left => 0.0,
+                wgpu_glyph::HorizontalAlign::Center => -line.width * 0.5,
+                wgpu_glyph::HorizontalAlign::Right => -line.width,
+            };
+            for mut glyph in line.glyphs {
+                glyph.glyph.position.x += shift;
+                glyph.glyph.position.y += top + line.ascent;
+                glyphs.push(glyph);
+            }
+            top += line.height;
+        }
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &wgpu_glyph::SectionGeometry) -> wgpu_glyph::ab_glyph::Rect {
+        let (x, y) = geometry.screen_position;
+        let (width, height) = geometry.bounds;
+        let (min_x, max_x) = match self.h_align {
+            wgpu_glyph::HorizontalAlign::Left => (x, x + width),
+            wgpu_glyph::HorizontalAlign::Center => (x - width * 0.5, x + width * 0.5),
+            wgpu_glyph::HorizontalAlign::Right => (x - width, x),
+        };
+        let (min_y, max_y) = match self.v_align {
+            wgpu_glyph::VerticalAlign::Top => (y, y + height),
+            wgpu_glyph::VerticalAlign::Center => (y - height * 0.5, y + height * 0.5),
+            wgpu_glyph::VerticalAlign::Bottom => (y - height, y),
+        };
+        wgpu_glyph::ab_glyph::Rect {
+            min: wgpu_glyph::ab_glyph::point(min_x.floor(), min_y.floor()),
+            max: wgpu_glyph::ab_glyph::point(max_x.ceil(), max_y.ceil()),
+        }
+    }
+}
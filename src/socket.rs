@@ -0,0 +1,128 @@
+/// Control socket in runtime directory of the user, or in temporary directory named after the
+/// user
+#[cfg(unix)]
+pub fn path() -> std::path::PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir).join("clock-app.sock"),
+        None => {
+            let uid = unsafe { libc::getuid() };
+            std::env::temp_dir().join(format!("clock-app-{}.sock", uid))
+        }
+    }
+}
+
+/// Serves line commands on the control socket, commands are handed over through the receiver
+#[cfg(unix)]
+pub fn spawn(
+    status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+) -> Result<std::sync::mpsc::Receiver<crate::control::Command>, crate::error::Error> {
+    let path = path();
+    if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+        return Err(crate::error::Error::AlreadyRunning(
+            path.display().to_string(),
+        ));
+    }
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        use std::io::Write;
+
+        log::debug!("listen control socket {}", path.display());
+        // socket left by previous instance, which no longer answers
+        let _ = std::fs::remove_file(&path);
+        let listener = match std::os::unix::net::UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::error!("failed to bind {}: {}", path.display(), err);
+                return;
+            }
+        };
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("failed to accept control connection: {}", err);
+                    continue;
+                }
+            };
+            let Ok(reader) = stream.try_clone() else {
+                continue;
+            };
+            for line in std::io::BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                log::debug!("control command {:?}", line);
+                let response = match parse(&line) {
+                    Ok(Some(command)) => {
+                        if sender.send(command).is_err() {
+                            return;
+                        }
+                        "ok".to_string()
+                    }
+                    Ok(None) => serde_json::to_string(&*status.lock().unwrap()).unwrap(),
+                    Err(err) => format!("error: {}", err),
+                };
+                if writeln!(stream, "{}", response).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+#[cfg(not(unix))]
+pub fn spawn(
+    _status: std::sync::Arc<std::sync::Mutex<crate::control::Status>>,
+) -> Result<std::sync::mpsc::Receiver<crate::control::Command>, crate::error::Error> {
+    log::error!("control socket is not supported on this platform");
+    Ok(std::sync::mpsc::channel().1)
+}
+
+/// Sends a line command to the running instance and returns its response
+#[cfg(unix)]
+pub fn send(line: &str) -> Result<String, Box<dyn std::error::Error>> {
+    use std::io::BufRead;
+    use std::io::Write;
+
+    let path = path();
+    let mut stream = std::os::unix::net::UnixStream::connect(&path)
+        .map_err(|err| format!("failed to connect {}: {}", path.display(), err))?;
+    writeln!(stream, "{}", line)?;
+    let mut response = String::new();
+    std::io::BufReader::new(stream).read_line(&mut response)?;
+    Ok(response.trim_end().to_string())
+}
+
+#[cfg(not(unix))]
+pub fn send(_line: &str) -> Result<String, Box<dyn std::error::Error>> {
+    Err("control socket is not supported on this platform".into())
+}
+
+/// Command of the line, `None` for status query
+#[cfg(unix)]
+fn parse(line: &str) -> Result<Option<crate::control::Command>, String> {
+    use crate::control::Command;
+
+    let (name, argument) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+    let command = match name {
+        "status" => return Ok(None),
+        "next" => Command::NextPicture,
+        "previous" => Command::PreviousPicture,
+        "pause" => Command::SetPaused(true),
+        "resume" => Command::SetPaused(false),
+        "show-text" => Command::SetMessage(Some(argument.to_string())),
+        "clear-text" => Command::SetMessage(None),
+        "alarm" => Command::TriggerAlarm,
+        "dismiss" => Command::DismissAlarm,
+        "profile" => Command::SetProfile((!argument.is_empty()).then(|| argument.to_string())),
+        "brightness" => Command::SetBrightness(
+            argument
+                .parse()
+                .map_err(|_| format!("invalid brightness: {:?}", argument))?,
+        ),
+        _ => return Err(format!("unknown command: {:?}", name)),
+    };
+    Ok(Some(command))
+}
@@ -0,0 +1,35 @@
+pub fn spawn(interval: std::time::Duration) -> std::sync::mpsc::Receiver<String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut system = sysinfo::System::new();
+        let mut components = sysinfo::Components::new_with_refreshed_list();
+        // cpu usage is measured between two refreshes
+        system.refresh_cpu();
+        loop {
+            std::thread::sleep(interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL));
+            log::debug!("sample system stats");
+            system.refresh_cpu();
+            system.refresh_memory();
+            components.refresh();
+            if sender.send(format(&system, &components)).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn format(system: &sysinfo::System, components: &sysinfo::Components) -> String {
+    let cpu = system.global_cpu_info().cpu_usage();
+    let memory = system.used_memory() as f32 / system.total_memory().max(1) as f32 * 100.0;
+    // hottest sensor stands for SoC temperature
+    let temperature = components
+        .iter()
+        .map(|component| component.temperature())
+        .filter(|temperature| temperature.is_finite())
+        .reduce(f32::max);
+    match temperature {
+        Some(temperature) => format!("cpu {:.0}% mem {:.0}% {:.1}°C", cpu, memory, temperature),
+        None => format!("cpu {:.0}% mem {:.0}%", cpu, memory),
+    }
+}
@@ -0,0 +1,45 @@
+pub struct Stopwatch {
+    elapsed: std::time::Duration,
+    start_instant: Option<std::time::Instant>,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self {
+            elapsed: std::time::Duration::ZERO,
+            start_instant: None,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        match self.start_instant.take() {
+            Some(start_instant) => {
+                log::debug!("stop stopwatch");
+                self.elapsed += start_instant.elapsed();
+            }
+            None => {
+                log::debug!("start stopwatch");
+                self.start_instant = Some(std::time::Instant::now());
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        log::debug!("reset stopwatch");
+        self.elapsed = std::time::Duration::ZERO;
+        self.start_instant = self.start_instant.map(|_| std::time::Instant::now());
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.start_instant.is_some()
+    }
+
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.elapsed
+            + self
+                .start_instant
+                .map_or(std::time::Duration::ZERO, |start_instant| {
+                    start_instant.elapsed()
+                })
+    }
+}
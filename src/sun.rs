@@ -0,0 +1,71 @@
+/// Observer location on earth [deg], north and east are positive
+#[derive(Clone, Copy)]
+pub struct Sun {
+    latitude: f64,
+    longitude: f64,
+}
+
+/// Color multiplier of picture at sunrise and sunset
+const WARM_TINT: [f32; 3] = [1.0, 0.8, 0.6];
+
+/// Time around sunrise and sunset fading warm tint in and out [s]
+const WARM_DURATION: f64 = 3600.0;
+
+impl Sun {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Sunrise and sunset of the solar day containing the instant, `None` in polar day or night
+    pub fn times(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)> {
+        // sunrise equation, accurate within a minute or so
+        let solar_now = now + chrono::Duration::seconds((self.longitude * 240.0) as i64);
+        let days = solar_now.date_naive() - chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let mean_noon = days.num_days() as f64 + 0.0008 - self.longitude / 360.0;
+        let anomaly = (357.5291 + 0.98560028 * mean_noon)
+            .rem_euclid(360.0)
+            .to_radians();
+        let center =
+            1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+        let ecliptic_longitude = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+            .rem_euclid(360.0)
+            .to_radians();
+        let transit =
+            mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+        let declination = (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+        let latitude = self.latitude.to_radians();
+        let cos_hour_angle = ((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+            / (latitude.cos() * declination.cos());
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+        let hour_angle = cos_hour_angle.acos().to_degrees() / 360.0;
+
+        // days from 2000-01-01 12:00 UTC, whose unix time is 946728000
+        let at = |days: f64| {
+            use chrono::TimeZone;
+            let timestamp = 946728000 + (days * 86400.0) as i64;
+            chrono::Utc.timestamp_opt(timestamp, 0).unwrap()
+        };
+        Some((at(transit - hour_angle), at(transit + hour_angle)))
+    }
+
+    /// Color multiplier warming picture around sunrise and sunset
+    pub fn tint(&self, now: chrono::DateTime<chrono::Utc>) -> [f32; 3] {
+        let Some((sunrise, sunset)) = self.times(now) else {
+            return [1.0; 3];
+        };
+        let distance = [sunrise, sunset]
+            .into_iter()
+            .map(|time| (now - time).num_seconds().abs() as f64)
+            .fold(f64::MAX, f64::min);
+        let warmth = (1.0 - distance / WARM_DURATION).max(0.0) as f32;
+        WARM_TINT.map(|channel| 1.0 + (channel - 1.0) * warmth)
+    }
+}
@@ -0,0 +1,89 @@
+/// Reports state to systemd through `$NOTIFY_SOCKET`, for units of `Type=notify`
+pub struct Notifier {
+    #[cfg(unix)]
+    socket: std::os::unix::net::UnixDatagram,
+    /// Half the watchdog timeout of the unit, if it has one
+    ping_interval: Option<std::time::Duration>,
+    ping_instant: std::time::Instant,
+}
+
+impl Notifier {
+    /// `None` if not started by systemd with notify access
+    #[cfg(unix)]
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("NOTIFY_SOCKET").ok()?;
+        let socket = std::os::unix::net::UnixDatagram::unbound()
+            .and_then(|socket| {
+                match path.strip_prefix('@') {
+                    #[cfg(target_os = "linux")]
+                    Some(name) => {
+                        use std::os::linux::net::SocketAddrExt;
+
+                        let address = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                        socket.connect_addr(&address)?
+                    }
+                    _ => socket.connect(&path)?,
+                }
+                Ok(socket)
+            })
+            .map_err(|err| log::error!("failed to connect notify socket {}: {}", path, err))
+            .ok()?;
+
+        // watchdog is meant for the main process only
+        let pid = std::env::var("WATCHDOG_PID")
+            .ok()
+            .and_then(|pid| pid.parse::<u32>().ok());
+        let ping_interval = std::env::var("WATCHDOG_USEC")
+            .ok()
+            .and_then(|usec| usec.parse::<u64>().ok())
+            .filter(|_| pid.is_none_or(|pid| pid == std::process::id()))
+            .map(|usec| std::time::Duration::from_micros(usec / 2));
+        log::debug!("notify systemd, watchdog ping interval {:?}", ping_interval);
+
+        Some(Self {
+            socket,
+            ping_interval,
+            ping_instant: std::time::Instant::now(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_env() -> Option<Self> {
+        None
+    }
+
+    pub fn ready(&self) {
+        self.send("READY=1");
+    }
+
+    pub fn stopping(&self) {
+        self.send("STOPPING=1");
+    }
+
+    /// Tells the watchdog the process is alive, at most once per ping interval
+    pub fn ping(&mut self) {
+        if self
+            .ping_interval
+            .is_some_and(|interval| interval <= self.ping_instant.elapsed())
+        {
+            self.send("WATCHDOG=1");
+            self.ping_instant = std::time::Instant::now();
+        }
+    }
+
+    /// Time the next ping is due
+    pub fn next_ping(&self) -> Option<std::time::Instant> {
+        self.ping_interval
+            .map(|interval| self.ping_instant + interval)
+    }
+
+    #[cfg(unix)]
+    fn send(&self, state: &str) {
+        if let Err(err) = self.socket.send(state.as_bytes()) {
+            log::warn!("failed to notify systemd {}: {}", state, err);
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn send(&self, _state: &str) {}
+}
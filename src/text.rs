@@ -1,71 +1,834 @@
+/// Longer side of the picture sampled for text contrast [px]
+const BACKGROUND_SIZE: u32 = 64;
+/// Luminance of the picture above which the dark text color is used
+const CONTRAST_THRESHOLD: f32 = 0.6;
+/// Side of glyph atlas relative to the largest text size, room for a few dozen glyphs of it
+const GLYPH_CACHE_SCALE: f32 = 6.0;
+
+#[derive(Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+pub enum TimeFormat {
+    #[value(name = "24h")]
+    #[serde(rename = "24h")]
+    H24,
+    #[value(name = "12h")]
+    #[serde(rename = "12h")]
+    H12,
+}
+
+impl TimeFormat {
+    fn pattern(self) -> &'static str {
+        match self {
+            Self::H24 => "%H:%M:%S",
+            Self::H12 => "%I:%M:%S %p",
+        }
+    }
+
+    /// Pattern without seconds
+    pub fn short_pattern(self) -> &'static str {
+        match self {
+            Self::H24 => "%H:%M",
+            Self::H12 => "%I:%M %p",
+        }
+    }
+}
+
+/// Point of text placed at text position
+#[derive(Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Top left corner of a box of the size placed at position
+    pub fn place(self, (x, y): (f32, f32), (width, height): (f32, f32)) -> (f32, f32) {
+        use wgpu_glyph::HorizontalAlign;
+        use wgpu_glyph::VerticalAlign;
+
+        let (h_align, v_align) = self.align();
+        let left = match h_align {
+            HorizontalAlign::Left => x,
+            HorizontalAlign::Center => x - width * 0.5,
+            HorizontalAlign::Right => x - width,
+        };
+        let top = match v_align {
+            VerticalAlign::Top => y,
+            VerticalAlign::Center => y - height * 0.5,
+            VerticalAlign::Bottom => y - height,
+        };
+        (left, top)
+    }
+
+    fn align(self) -> (wgpu_glyph::HorizontalAlign, wgpu_glyph::VerticalAlign) {
+        use wgpu_glyph::HorizontalAlign;
+        use wgpu_glyph::VerticalAlign;
+
+        match self {
+            Self::TopLeft => (HorizontalAlign::Left, VerticalAlign::Top),
+            Self::Top => (HorizontalAlign::Center, VerticalAlign::Top),
+            Self::TopRight => (HorizontalAlign::Right, VerticalAlign::Top),
+            Self::Left => (HorizontalAlign::Left, VerticalAlign::Center),
+            Self::Center => (HorizontalAlign::Center, VerticalAlign::Center),
+            Self::Right => (HorizontalAlign::Right, VerticalAlign::Center),
+            Self::BottomLeft => (HorizontalAlign::Left, VerticalAlign::Bottom),
+            Self::Bottom => (HorizontalAlign::Center, VerticalAlign::Bottom),
+            Self::BottomRight => (HorizontalAlign::Right, VerticalAlign::Bottom),
+        }
+    }
+}
+
+#[derive(Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Clock {
+    pub tz: chrono_tz::Tz,
+    pub label: Option<String>,
+}
+
+impl std::str::FromStr for Clock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tz = None;
+        let mut label = None;
+        for pair in s.split(',') {
+            match pair.split_once('=') {
+                Some(("tz", value)) => tz = Some(value.parse::<chrono_tz::Tz>()?),
+                Some(("label", value)) => label = Some(value.to_string()),
+                _ => return Err(format!("invalid clock entry: {:?}", pair)),
+            }
+        }
+        let tz = tz.ok_or("missing tz in clock")?;
+        Ok(Self { tz, label })
+    }
+}
+
+/// Date written as `YYYY-MM-DD`, or `MM-DD` for every year
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub enum Day {
+    Once(chrono::NaiveDate),
+    Yearly(u32, u32),
+}
+
+impl Day {
+    /// Days from today to the next occurrence, `None` after it passed
+    fn days_from(self, today: chrono::NaiveDate) -> Option<i64> {
+        use chrono::Datelike;
+
+        let date = match self {
+            Self::Once(date) => date,
+            Self::Yearly(month, day) => [today.year(), today.year() + 1]
+                .into_iter()
+                .filter_map(|year| chrono::NaiveDate::from_ymd_opt(year, month, day))
+                .find(|date| today <= *date)?,
+        };
+        Some((date - today).num_days()).filter(|days| 0 <= *days)
+    }
+}
+
+impl std::str::FromStr for Day {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Self::Once(date));
+        }
+        // leap year accepts every day of year
+        chrono::NaiveDate::parse_from_str(&format!("2000-{}", s), "%Y-%m-%d")
+            .map(|date| {
+                use chrono::Datelike;
+                Self::Yearly(date.month(), date.day())
+            })
+            .map_err(|_| format!("invalid date: {:?}", s))
+    }
+}
+
+impl TryFrom<String> for Day {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Event counted down in days
+#[derive(Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DaysUntil {
+    pub date: Day,
+    pub label: String,
+}
+
+impl std::str::FromStr for DaysUntil {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut date = None;
+        let mut label = None;
+        for pair in s.split(',') {
+            match pair.split_once('=') {
+                Some(("date", value)) => date = Some(value.parse::<Day>()?),
+                Some(("label", value)) => label = Some(value.to_string()),
+                _ => return Err(format!("invalid days-until entry: {:?}", pair)),
+            }
+        }
+        let date = date.ok_or("missing date in days-until")?;
+        let label = label.ok_or("missing label in days-until")?;
+        Ok(Self { date, label })
+    }
+}
+
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Countdown(chrono::NaiveDateTime);
+
+impl std::str::FromStr for Countdown {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+            .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M"))
+            .map(Self)
+            .map_err(|_| format!("invalid countdown target: {:?}", s))
+    }
+}
+
+impl TryFrom<String> for Countdown {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Countdown {
+    /// Target in the timezone, `None` if it is skipped by daylight saving time
+    pub fn target(&self, timezone: Option<chrono_tz::Tz>) -> Option<chrono::DateTime<chrono::Utc>> {
+        use chrono::TimeZone;
+
+        match timezone {
+            Some(timezone) => timezone
+                .from_local_datetime(&self.0)
+                .earliest()
+                .map(|target| target.with_timezone(&chrono::Utc)),
+            None => chrono::Local
+                .from_local_datetime(&self.0)
+                .earliest()
+                .map(|target| target.with_timezone(&chrono::Utc)),
+        }
+    }
+}
+
+impl std::fmt::Display for Countdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%dT%H:%M:%S"))
+    }
+}
+
+/// RGBA color written as `#rrggbb` or `#rrggbbaa`
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color(pub [f32; 4]);
+
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6 || hex.len() == 8)
+            .ok_or_else(|| format!("invalid color: {:?}", s))?;
+        let mut color = [1.0; 4];
+        for (i, channel) in color.iter_mut().enumerate().take(hex.len() / 2) {
+            let value = hex
+                .get(i * 2..i * 2 + 2)
+                .and_then(|value| u8::from_str_radix(value, 16).ok())
+                .ok_or_else(|| format!("invalid color: {:?}", s))?;
+            *channel = value as f32 / 255.0;
+        }
+        Ok(Self(color))
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Content of text element
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementKind {
+    /// Time, or stopwatch and countdown when active
+    #[default]
+    Time,
+    Date,
+    /// World clocks spread across the window width
+    Clocks,
+    Event,
+    Drift,
+    Weather,
+    /// CPU load, memory usage and temperature
+    Stats,
+    /// Battery level, in warning color when low
+    Battery,
+    /// Message set through remote control
+    Message,
+    /// Sunrise and sunset times of today
+    Sun,
+    /// Moon phase name, shown under moon icon
+    Moon,
+    /// Title and date taken of the picture shown
+    Caption,
+    /// Greeting of the active theme
+    Greeting,
+    /// Days until events, one per line
+    #[serde(rename = "days-until")]
+    DaysUntil,
+    /// ISO week number and day of year
+    Week,
+}
+
+/// Independent text element of layout, unset fields fall back to defaults of its kind
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Element {
+    pub kind: ElementKind,
+    /// Format string of time and date elements, or of sunrise and sunset times
+    pub format: Option<String>,
+    pub size: Option<f32>,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    /// Offset added to position [px]
+    pub offset: (f32, f32),
+    pub anchor: Option<Anchor>,
+    /// Time between text updates [ms], 0 updates every frame
+    pub interval: u64,
+}
+
+impl Element {
+    fn default_size(&self) -> f32 {
+        match self.kind {
+            ElementKind::Time => 128.0,
+            ElementKind::Date | ElementKind::Message => 32.0,
+            ElementKind::Clocks => 48.0,
+            ElementKind::Event | ElementKind::Weather => 24.0,
+            ElementKind::Drift | ElementKind::Stats | ElementKind::Battery => 20.0,
+            ElementKind::Sun | ElementKind::Moon | ElementKind::Caption => 20.0,
+            ElementKind::Greeting => 40.0,
+            ElementKind::DaysUntil | ElementKind::Week => 20.0,
+        }
+    }
+
+    fn default_position(&self) -> (f32, f32) {
+        match self.kind {
+            ElementKind::Time | ElementKind::Date => (0.5, 0.5),
+            ElementKind::Clocks => (0.5, 0.85),
+            ElementKind::Event => (0.5, 0.7),
+            ElementKind::Drift => (0.15, 0.05),
+            ElementKind::Weather => (0.9, 0.2),
+            ElementKind::Stats => (0.15, 0.95),
+            ElementKind::Battery => (0.9, 0.95),
+            ElementKind::Message => (0.5, 0.2),
+            ElementKind::Sun => (0.5, 0.05),
+            ElementKind::Moon => (0.1, 0.2),
+            ElementKind::Caption => (0.98, 0.88),
+            ElementKind::Greeting => (0.5, 0.1),
+            ElementKind::DaysUntil => (0.03, 0.85),
+            ElementKind::Week => (0.97, 0.05),
+        }
+    }
+
+    fn default_anchor(&self) -> Anchor {
+        match self.kind {
+            ElementKind::Caption => Anchor::BottomRight,
+            ElementKind::DaysUntil => Anchor::Left,
+            ElementKind::Week => Anchor::TopRight,
+            _ => Anchor::Center,
+        }
+    }
+}
+
+/// Builds layout from text options, used when no element is configured
+fn default_layout(config: &crate::config::Config) -> Vec<Element> {
+    let anchor = config.text_anchor;
+    let time = Element {
+        kind: ElementKind::Time,
+        size: Some(config.text_size),
+        x: Some(config.text_x),
+        y: Some(config.text_y),
+        anchor: Some(anchor),
+        ..Default::default()
+    };
+    if config.widget {
+        return vec![time];
+    }
+
+    let mut elements = vec![time];
+    if config.show_date {
+        // date line follows time line as if they are one paragraph
+        let date_size = config.text_size * 0.25;
+        let height = config.face.height(config.text_size);
+        let offset = match anchor {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => height,
+            Anchor::Left | Anchor::Center | Anchor::Right => (height + date_size) * 0.5,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => date_size,
+        };
+        elements.push(Element {
+            kind: ElementKind::Date,
+            size: Some(date_size),
+            x: Some(config.text_x),
+            y: Some(config.text_y),
+            offset: (0.0, offset),
+            anchor: Some(anchor),
+            ..Default::default()
+        });
+    }
+    for kind in [
+        ElementKind::Clocks,
+        ElementKind::Event,
+        ElementKind::Drift,
+        ElementKind::Weather,
+        ElementKind::Stats,
+        ElementKind::Battery,
+        ElementKind::Message,
+        ElementKind::Sun,
+        ElementKind::Moon,
+        ElementKind::Greeting,
+        ElementKind::DaysUntil,
+    ] {
+        elements.push(Element {
+            kind,
+            ..Default::default()
+        });
+    }
+    if config.show_week {
+        elements.push(Element {
+            kind: ElementKind::Week,
+            ..Default::default()
+        });
+    }
+    if config.caption {
+        elements.push(Element {
+            kind: ElementKind::Caption,
+            ..Default::default()
+        });
+    }
+    elements
+}
+
+/// Texts placed at relative position, color overrides text color
+#[derive(PartialEq)]
+struct Section {
+    texts: Vec<(String, f32)>,
+    position: (f32, f32),
+    color: Option<[f32; 4]>,
+}
+
+impl Section {
+    fn new(texts: Vec<(String, f32)>, position: (f32, f32)) -> Self {
+        Self {
+            texts,
+            position,
+            color: None,
+        }
+    }
+}
+
+struct ElementState {
+    element: Element,
+    sections: Vec<Section>,
+    /// Period of wall clock the sections are made in
+    updated: Option<i64>,
+}
+
 pub struct TextPipeline {
     staging_belt: wgpu::util::StagingBelt,
     glyph_blush: wgpu_glyph::GlyphBrush<()>,
+    /// Paths of font and emoji font the glyphs are rasterized from
+    fonts: (Option<String>, Option<String>),
+    layout: crate::shaping::ShapedLayout,
+    emoji_pipeline: Option<crate::emoji::EmojiPipeline>,
+    face_pipeline: Option<crate::face::FacePipeline>,
     target_width: u32,
     target_height: u32,
+    time_format: TimeFormat,
+    format: Option<String>,
+    timezone: Option<chrono_tz::Tz>,
+    clocks: Vec<Clock>,
+    days_until: Vec<DaysUntil>,
+    date_format: String,
+    countdown: Option<chrono::DateTime<chrono::Utc>>,
+    stopwatch: Option<std::time::Duration>,
+    weather: Option<String>,
+    event: Option<crate::calendar::Event>,
+    drift: Option<f64>,
+    stats: Option<String>,
+    battery: Option<crate::battery::Battery>,
+    message: Option<String>,
+    sun: Option<crate::sun::Sun>,
+    moon: Option<String>,
+    caption: Option<String>,
+    greeting: Option<String>,
+    battery_low: u32,
+    battery_low_color: [f32; 4],
+    elements: Vec<ElementState>,
+    text_color: [f32; 4],
+    text_dark_color: Option<[f32; 4]>,
+    /// Luminance of picture shrunk, with size of the picture
+    background: Option<(image::GrayImage, (u32, u32))>,
+    picture_fit: crate::picture::PictureFit,
+    letterbox_color: [f32; 4],
+    palette: bool,
+    palette_color: Option<[f32; 3]>,
+    text_outline: f32,
+    text_outline_color: [f32; 4],
+    text_shadow: f32,
+    text_shadow_color: [f32; 4],
+    tint: [f32; 3],
+    shift: (f32, f32),
+    blink_colon: bool,
+    /// Whether colons of time are shown in this half of second
+    colon_shown: bool,
+    /// Whether output differs from the last drawn one, other than texts of sections
+    dirty: bool,
 }
 
 impl TextPipeline {
     pub fn new(
         device: &wgpu::Device,
         target_format: wgpu::TextureFormat,
+        sample_count: u32,
         target_width: u32,
         target_height: u32,
+        config: &crate::config::Config,
     ) -> Self {
         let staging_belt = wgpu::util::StagingBelt::new(1024);
-        let font = wgpu_glyph::ab_glyph::FontArc::try_from_slice(include_bytes!(
-            "../assets/fonts/Inconsolata-Bold.ttf"
-        ))
-        .unwrap();
-        let glyph_blush =
-            wgpu_glyph::GlyphBrushBuilder::using_font(font).build(device, target_format);
+        let (font_data, font) = config
+            .font
+            .as_deref()
+            .and_then(|path| match load_font(path) {
+                Ok(font) => Some(font),
+                Err(err) => {
+                    log::error!("failed to load font {}, use built-in font: {}", path, err);
+                    None
+                }
+            })
+            .unwrap_or_else(|| {
+                let data = include_bytes!("../assets/fonts/Inconsolata-Bold.ttf");
+                let font = wgpu_glyph::ab_glyph::FontArc::try_from_slice(data).unwrap();
+                (data.to_vec(), font)
+            });
+        let emoji_font = config
+            .emoji_font
+            .as_deref()
+            .and_then(|path| match load_font(path) {
+                Ok(font) => Some(font),
+                Err(err) => {
+                    log::error!("failed to load emoji font {}: {}", path, err);
+                    None
+                }
+            });
+        let mut fonts = vec![font.clone()];
+        let mut fonts_data = vec![font_data];
+        if let Some((data, font)) = emoji_font.clone() {
+            fonts.push(font);
+            fonts_data.push(data);
+        }
+        let elements = if config.elements.is_empty() {
+            default_layout(config)
+        } else {
+            config.elements.clone()
+        };
+
+        // glyphs are rasterized once per glyph and size into the atlas, sized up front for the
+        // largest text so that it is not regrown, and outlines at subpixel offsets share them
+        let max_size = elements
+            .iter()
+            .map(|element| element.size.unwrap_or_else(|| element.default_size()))
+            .fold(0.0, f32::max);
+        let cache_size = ((max_size * GLYPH_CACHE_SCALE) as u32)
+            .next_power_of_two()
+            .clamp(256, device.limits().max_texture_dimension_2d);
+        let glyph_blush = wgpu_glyph::GlyphBrushBuilder::using_fonts(fonts)
+            .initial_cache_size((cache_size, cache_size))
+            .draw_cache_position_tolerance(1.0)
+            .multisample_state(wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            })
+            .build(device, target_format);
+        let layout = crate::shaping::ShapedLayout::new(fonts_data);
+        let face_pipeline =
+            crate::face::FacePipeline::new(device, target_format, sample_count, &font, config);
+        let emoji_pipeline = emoji_font.map(|(_, font)| {
+            crate::emoji::EmojiPipeline::new(device, target_format, sample_count, font)
+        });
 
         Self {
             staging_belt,
             glyph_blush,
+            fonts: (config.font.clone(), config.emoji_font.clone()),
+            layout,
+            emoji_pipeline,
+            face_pipeline,
             target_width,
             target_height,
+            time_format: config.time_format,
+            format: config.format.clone(),
+            timezone: config.timezone,
+            clocks: config.clocks.clone(),
+            days_until: config.days_until.clone(),
+            date_format: config.date_format.clone(),
+            // checked to exist by `Args::into_config`
+            countdown: config
+                .countdown
+                .and_then(|countdown| countdown.target(config.timezone)),
+            stopwatch: None,
+            weather: None,
+            event: None,
+            drift: None,
+            stats: None,
+            battery: None,
+            message: None,
+            sun: config
+                .latitude
+                .zip(config.longitude)
+                .map(|(latitude, longitude)| crate::sun::Sun::new(latitude, longitude)),
+            moon: None,
+            caption: None,
+            greeting: config.greeting.clone(),
+            battery_low: config.battery_low,
+            battery_low_color: config.battery_low_color.0,
+            blink_colon: config.blink_colon,
+            colon_shown: true,
+            elements: elements
+                .into_iter()
+                .map(|element| ElementState {
+                    element,
+                    sections: vec![],
+                    updated: None,
+                })
+                .collect(),
+            text_color: config.text_color.0,
+            text_dark_color: config.auto_contrast.then_some(config.text_dark_color.0),
+            background: None,
+            picture_fit: config.picture_fit,
+            letterbox_color: config.letterbox_color.0,
+            palette: config.palette,
+            palette_color: None,
+            text_outline: config.text_outline,
+            text_outline_color: config.text_outline_color.0,
+            text_shadow: config.text_shadow,
+            text_shadow_color: config.text_shadow_color.0,
+            tint: [1.0; 3],
+            shift: (0.0, 0.0),
+            dirty: true,
+        }
+    }
+
+    /// Rebuilds from reloaded config, keeping values received from workers and pictures
+    pub fn reload(
+        &mut self,
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        sample_count: u32,
+        config: &crate::config::Config,
+    ) {
+        let mut text_pipeline = Self::new(
+            device,
+            target_format,
+            sample_count,
+            self.target_width,
+            self.target_height,
+            config,
+        );
+        text_pipeline.stopwatch = self.stopwatch;
+        text_pipeline.weather = self.weather.take();
+        text_pipeline.event = self.event.take();
+        text_pipeline.drift = self.drift;
+        text_pipeline.stats = self.stats.take();
+        text_pipeline.battery = self.battery.take();
+        text_pipeline.message = self.message.take();
+        text_pipeline.moon = self.moon.take();
+        text_pipeline.caption = self.caption.take();
+        text_pipeline.background = self.background.take();
+        text_pipeline.palette_color = self.palette_color;
+        text_pipeline.tint = self.tint;
+        text_pipeline.shift = self.shift;
+        // glyphs rasterized so far are kept unless fonts change
+        if text_pipeline.fonts == self.fonts {
+            std::mem::swap(&mut text_pipeline.glyph_blush, &mut self.glyph_blush);
         }
+        *self = text_pipeline;
     }
 
     pub fn resize(&mut self, target_width: u32, target_height: u32) {
         self.target_width = target_width;
         self.target_height = target_height;
+        self.dirty = true;
+    }
+
+    pub fn set_stopwatch(&mut self, elapsed: Option<std::time::Duration>) {
+        self.stopwatch = elapsed;
+    }
+
+    pub fn set_weather(&mut self, weather: Option<String>) {
+        self.weather = weather;
+    }
+
+    pub fn set_event(&mut self, event: Option<crate::calendar::Event>) {
+        self.event = event;
+    }
+
+    pub fn set_drift(&mut self, drift: Option<f64>) {
+        self.drift = drift;
+    }
+
+    pub fn set_stats(&mut self, stats: Option<String>) {
+        self.stats = stats;
+    }
+
+    pub fn set_battery(&mut self, battery: Option<crate::battery::Battery>) {
+        self.battery = battery;
+    }
+
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    pub fn set_greeting(&mut self, greeting: Option<String>) {
+        self.greeting = greeting;
+    }
+
+    pub fn set_text_color(&mut self, color: [f32; 4]) {
+        self.text_color = color;
+    }
+
+    pub fn set_caption(&mut self, caption: Option<String>) {
+        self.caption = caption;
+    }
+
+    pub fn set_moon(&mut self, moon: Option<String>) {
+        self.moon = moon;
+    }
+
+    /// Picture behind texts, sampled to pick the dark text color over bright parts
+    /// and the palette of text colors
+    pub fn set_background(&mut self, image: &image::RgbaImage) {
+        if self.palette {
+            self.palette_color = Some(crate::palette::dominant(image));
+            self.dirty = true;
+        }
+        if self.text_dark_color.is_some() {
+            let (width, height) = image.dimensions();
+            let scale = BACKGROUND_SIZE as f32 / width.max(height) as f32;
+            let thumbnail = image::imageops::thumbnail(
+                image,
+                ((width as f32 * scale) as u32).max(1),
+                ((height as f32 * scale) as u32).max(1),
+            );
+            self.background = Some((image::imageops::grayscale(&thumbnail), (width, height)));
+            self.dirty = true;
+        }
+    }
+
+    /// Color multiplier of all texts
+    pub fn set_tint(&mut self, tint: [f32; 3]) {
+        self.dirty |= self.tint != tint;
+        self.tint = tint;
+    }
+
+    /// Offset of all texts [px]
+    pub fn set_shift(&mut self, shift: (f32, f32)) {
+        self.dirty |= self.shift != shift;
+        self.shift = shift;
+    }
+
+    /// Whether the face is in motion between updates of texts
+    pub fn is_animating(&self) -> bool {
+        self.face_pipeline
+            .as_ref()
+            .is_some_and(|face_pipeline| face_pipeline.is_animating())
+    }
+
+    /// Refreshes texts due to update, and tells whether output changed since the last call
+    pub fn update(&mut self) -> bool {
+        let utc = chrono::Utc::now();
+        let mut changed = std::mem::take(&mut self.dirty);
+        if self.blink_colon {
+            // only the color of colons changes, keeping the layout of time
+            let colon_shown = utc.timestamp_subsec_millis() < 500;
+            changed |= colon_shown != self.colon_shown;
+            self.colon_shown = colon_shown;
+        }
+        let mut elements = std::mem::take(&mut self.elements);
+        for state in &mut elements {
+            // periods follow wall clock so that texts change on second boundaries
+            let period = utc
+                .timestamp_millis()
+                .div_euclid(state.element.interval.max(1) as i64);
+            if state.updated != Some(period) {
+                let sections = self.sections(&state.element, &utc);
+                if sections != state.sections {
+                    state.sections = sections;
+                    changed = true;
+                }
+                state.updated = Some(period);
+            }
+        }
+        self.elements = elements;
+        changed
     }
 
     pub fn draw(
         &mut self,
         device: &wgpu::Device,
+        queue: &wgpu::Queue,
         view: &wgpu::TextureView,
         encoder: &mut wgpu::CommandEncoder,
     ) {
         self.staging_belt.recall();
 
-        let utc = chrono::Local::now();
-        let date_text = utc.format("%Y/%m/%d %a\n").to_string();
-        let time_text = utc.format("%H:%M:%S\n").to_string();
-        self.glyph_blush.queue(
-            wgpu_glyph::Section::default()
-                .add_text(
-                    wgpu_glyph::Text::new(&date_text)
-                        .with_scale(32.0)
-                        .with_color([1.0, 1.0, 1.0, 1.0]),
-                )
-                .add_text(
-                    wgpu_glyph::Text::new(&time_text)
-                        .with_scale(128.0)
-                        .with_color([1.0, 1.0, 1.0, 1.0]),
-                )
-                .with_screen_position((
-                    self.target_width as f32 * 0.5,
-                    self.target_height as f32 * 0.5,
-                ))
-                .with_layout(
-                    wgpu_glyph::Layout::default()
-                        .h_align(wgpu_glyph::HorizontalAlign::Center)
-                        .v_align(wgpu_glyph::VerticalAlign::Center),
-                ),
-        );
+        let mut elements = std::mem::take(&mut self.elements);
+        for state in &mut elements {
+            let anchor = state
+                .element
+                .anchor
+                .unwrap_or_else(|| state.element.default_anchor());
+            for section in &state.sections {
+                let color = section
+                    .color
+                    .unwrap_or_else(|| self.contrast_color(section, state.element.offset, anchor));
+                if let (ElementKind::Time, Some(_)) = (state.element.kind, &self.face_pipeline) {
+                    self.queue_face(queue, section, state.element.offset, anchor, color);
+                    continue;
+                }
+                let blink = self.blink_colon && state.element.kind == ElementKind::Time;
+                self.queue_texts(
+                    queue,
+                    &section.texts,
+                    section.position,
+                    state.element.offset,
+                    anchor,
+                    color,
+                    blink,
+                );
+            }
+        }
+        self.elements = elements;
+
         self.glyph_blush
             .draw_queued(
                 device,
@@ -76,7 +839,587 @@ impl TextPipeline {
                 self.target_height,
             )
             .unwrap();
+        if let Some(emoji_pipeline) = &mut self.emoji_pipeline {
+            emoji_pipeline.draw(device, view, encoder);
+        }
+        if let Some(face_pipeline) = &mut self.face_pipeline {
+            face_pipeline.draw(device, view, encoder);
+        }
 
         self.staging_belt.finish();
     }
+
+    fn sections(&self, element: &Element, utc: &chrono::DateTime<chrono::Utc>) -> Vec<Section> {
+        let size = element.size.unwrap_or_else(|| element.default_size());
+        let (default_x, default_y) = element.default_position();
+        let position = (
+            element.x.unwrap_or(default_x),
+            element.y.unwrap_or(default_y),
+        );
+        match element.kind {
+            ElementKind::Time => {
+                let remaining = self
+                    .countdown
+                    .map(|target| target - *utc)
+                    .filter(|remaining| chrono::Duration::zero() < *remaining);
+                let format = element.format.as_ref().or(self.format.as_ref());
+                let text = match (self.stopwatch, remaining, format) {
+                    (Some(elapsed), _, _) => format_elapsed(elapsed),
+                    (None, Some(remaining), _) => format_remaining(remaining),
+                    (None, None, Some(format)) => self.format_time(utc, format),
+                    (None, None, None) => self.format_time(utc, self.time_format.pattern()),
+                };
+                vec![Section::new(vec![(text, size)], position)]
+            }
+            ElementKind::Date => {
+                let format = element.format.as_ref().unwrap_or(&self.date_format);
+                vec![Section::new(
+                    vec![(self.format_time(utc, format), size)],
+                    position,
+                )]
+            }
+            ElementKind::Week => {
+                let format = element.format.as_deref().unwrap_or("week %V, day %j");
+                vec![Section::new(
+                    vec![(self.format_time(utc, format), size)],
+                    position,
+                )]
+            }
+            ElementKind::Clocks => {
+                let clock_count = self.clocks.len();
+                self.clocks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, clock)| {
+                        let label = clock.label.as_deref().unwrap_or(clock.tz.name());
+                        let time_text = utc
+                            .with_timezone(&clock.tz)
+                            .format(self.time_format.pattern())
+                            .to_string();
+                        let x = (i as f32 + 0.5) / clock_count as f32;
+                        let texts = vec![(format!("{}\n", label), size * 0.5), (time_text, size)];
+                        Section::new(texts, (x, position.1))
+                    })
+                    .collect()
+            }
+            ElementKind::Event => self
+                .event
+                .iter()
+                .map(|event| {
+                    let text = format!(
+                        "{} {}",
+                        self.format_time(&event.start, "%a %H:%M"),
+                        event.summary
+                    );
+                    Section::new(vec![(text, size)], position)
+                })
+                .collect(),
+            ElementKind::Drift => self
+                .drift
+                .iter()
+                .map(|drift| {
+                    let text = format!("clock drift {:+.1}s", drift);
+                    Section::new(vec![(text, size)], position)
+                })
+                .collect(),
+            ElementKind::Weather => self
+                .weather
+                .iter()
+                .map(|weather| Section::new(vec![(weather.clone(), size)], position))
+                .collect(),
+            ElementKind::Stats => self
+                .stats
+                .iter()
+                .map(|stats| Section::new(vec![(stats.clone(), size)], position))
+                .collect(),
+            ElementKind::Battery => self
+                .battery
+                .iter()
+                .map(|battery| {
+                    let state = if battery.charging {
+                        "charging"
+                    } else {
+                        "battery"
+                    };
+                    let text = format!("{} {}%", state, battery.percent);
+                    let low = !battery.charging && battery.percent <= self.battery_low;
+                    Section {
+                        color: low.then_some(self.battery_low_color),
+                        ..Section::new(vec![(text, size)], position)
+                    }
+                })
+                .collect(),
+            ElementKind::Message => self
+                .message
+                .iter()
+                .map(|message| Section::new(vec![(message.clone(), size)], position))
+                .collect(),
+            ElementKind::Sun => self
+                .sun
+                .and_then(|sun| sun.times(*utc))
+                .map(|(sunrise, sunset)| {
+                    let format = element
+                        .format
+                        .as_deref()
+                        .unwrap_or(self.time_format.short_pattern());
+                    let text = format!(
+                        "sunrise {} sunset {}",
+                        self.format_time(&sunrise, format),
+                        self.format_time(&sunset, format)
+                    );
+                    Section::new(vec![(text, size)], position)
+                })
+                .into_iter()
+                .collect(),
+            ElementKind::Moon => self
+                .moon
+                .iter()
+                .map(|moon| Section::new(vec![(moon.clone(), size)], position))
+                .collect(),
+            ElementKind::Caption => self
+                .caption
+                .iter()
+                .map(|caption| Section::new(vec![(caption.clone(), size)], position))
+                .collect(),
+            ElementKind::Greeting => self
+                .greeting
+                .iter()
+                .map(|greeting| Section::new(vec![(greeting.clone(), size)], position))
+                .collect(),
+            ElementKind::DaysUntil => {
+                let today = match self.timezone {
+                    Some(timezone) => utc.with_timezone(&timezone).date_naive(),
+                    None => utc.with_timezone(&chrono::Local).date_naive(),
+                };
+                let lines = self
+                    .days_until
+                    .iter()
+                    .filter_map(|entry| {
+                        let text = match entry.date.days_from(today)? {
+                            0 => format!("{} today", entry.label),
+                            1 => format!("1 day until {}", entry.label),
+                            days => format!("{} days until {}", days, entry.label),
+                        };
+                        Some(text)
+                    })
+                    .collect::<Vec<_>>();
+                if lines.is_empty() {
+                    vec![]
+                } else {
+                    vec![Section::new(vec![(lines.join("\n"), size)], position)]
+                }
+            }
+        }
+    }
+
+    /// Queues texts over their drop shadow and outline, which are the same texts shifted, with
+    /// colons in runs of their own if blinking
+    #[allow(clippy::too_many_arguments)]
+    fn queue_texts(
+        &mut self,
+        queue: &wgpu::Queue,
+        texts: &[(String, f32)],
+        (x, y): (f32, f32),
+        (offset_x, offset_y): (f32, f32),
+        anchor: Anchor,
+        color: [f32; 4],
+        blink: bool,
+    ) {
+        let mut layers = vec![];
+        if 0.0 < self.text_shadow {
+            let color = self.accent_color(self.text_shadow_color);
+            layers.push(((self.text_shadow, self.text_shadow), color));
+        }
+        if 0.0 < self.text_outline {
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+                let offset = (
+                    angle.cos() * self.text_outline,
+                    angle.sin() * self.text_outline,
+                );
+                layers.push((offset, self.accent_color(self.text_outline_color)));
+            }
+        }
+        layers.push(((0.0, 0.0), color));
+
+        let x = self.target_width as f32 * x + offset_x + self.shift.0;
+        let y = self.target_height as f32 * y + offset_y + self.shift.1;
+        let (h_align, v_align) = anchor.align();
+        let layout = self.layout.with_align(h_align, v_align);
+        let runs = texts
+            .iter()
+            .flat_map(|(text, scale)| {
+                self.split_emoji(text)
+                    .into_iter()
+                    .map(move |(text, emoji)| (text, *scale, emoji))
+            })
+            .flat_map(|(text, scale, emoji)| {
+                let parts = match blink {
+                    true => split_colons(text),
+                    false => vec![text],
+                };
+                parts
+                    .into_iter()
+                    .map(move |text| (text, scale, emoji, blink && text == ":"))
+            })
+            .collect::<Vec<_>>();
+        for ((dx, dy), [r, g, b, a]) in layers {
+            // colors are written in sRGB, while glyphs are blended into linear target
+            let [r, g, b, a] = linear_color([r, g, b, a]);
+            let color = [r * self.tint[0], g * self.tint[1], b * self.tint[2], a];
+            let hidden = [color[0], color[1], color[2], 0.0];
+            let section = wgpu_glyph::Section::default()
+                .with_text(
+                    runs.iter()
+                        .map(|(text, scale, emoji, colon)| {
+                            let shown = !colon || self.colon_shown;
+                            wgpu_glyph::Text::new(text)
+                                .with_scale(*scale)
+                                .with_color(if shown { color } else { hidden })
+                                .with_font_id(wgpu_glyph::FontId(*emoji as usize))
+                        })
+                        .collect(),
+                )
+                .with_screen_position((x + dx, y + dy));
+
+            // color glyphs are drawn by emoji pipeline on the main layer only
+            if let (Some(emoji_pipeline), (0.0, 0.0)) = (&mut self.emoji_pipeline, (dx, dy)) {
+                use wgpu_glyph::GlyphCruncher;
+
+                let target_size = (self.target_width, self.target_height);
+                for glyph in self
+                    .glyph_blush
+                    .glyphs_custom_layout(section.clone(), &layout)
+                {
+                    if glyph.font_id == wgpu_glyph::FontId(1) {
+                        emoji_pipeline.queue(queue, &glyph.glyph, color, target_size);
+                    }
+                }
+            }
+            self.glyph_blush.queue_custom_layout(section, &layout);
+        }
+    }
+
+    /// Queues time drawn by face pipeline instead of glyphs, except letters of the face
+    fn queue_face(
+        &mut self,
+        queue: &wgpu::Queue,
+        section: &Section,
+        (offset_x, offset_y): (f32, f32),
+        anchor: Anchor,
+        color: [f32; 4],
+    ) {
+        let (x, y) = section.position;
+        let x = self.target_width as f32 * x + offset_x + self.shift.0;
+        let y = self.target_height as f32 * y + offset_y + self.shift.1;
+        let [r, g, b, a] = linear_color(color);
+        let linear = [r * self.tint[0], g * self.tint[1], b * self.tint[2], a];
+        let target_size = (self.target_width, self.target_height);
+        let colon_shown = !self.blink_colon || self.colon_shown;
+        let face_pipeline = self.face_pipeline.as_mut().unwrap();
+        let letters = section
+            .texts
+            .iter()
+            .flat_map(|(text, size)| {
+                face_pipeline.queue(
+                    queue,
+                    text,
+                    (x, y),
+                    *size,
+                    anchor,
+                    linear,
+                    target_size,
+                    colon_shown,
+                )
+            })
+            .collect::<Vec<_>>();
+        for letter in letters {
+            let [r, g, b, a] = color;
+            let color = if letter.lit {
+                color
+            } else {
+                [r, g, b, a * 0.2]
+            };
+            // letters are placed relative to the position of the section
+            let offset = (
+                offset_x + letter.position.0 - x,
+                offset_y + letter.position.1 - y,
+            );
+            self.queue_texts(
+                queue,
+                &[(letter.letter.to_string(), letter.size)],
+                section.position,
+                offset,
+                Anchor::Center,
+                color,
+                false,
+            );
+        }
+    }
+
+    /// Splits text into runs of the main font and the emoji font, the latter is used only for
+    /// characters missing in the main font
+    fn split_emoji<'a>(&self, text: &'a str) -> Vec<(&'a str, bool)> {
+        use wgpu_glyph::ab_glyph::Font;
+
+        let Some(emoji_pipeline) = &self.emoji_pipeline else {
+            return vec![(text, false)];
+        };
+        let font = &self.glyph_blush.fonts()[0];
+        let mut runs = vec![];
+        let mut start = 0;
+        let mut current = false;
+        for (i, c) in text.char_indices() {
+            let emoji = font.glyph_id(c).0 == 0 && emoji_pipeline.contains(c);
+            if emoji != current && start < i {
+                runs.push((&text[start..i], current));
+                start = i;
+            }
+            current = emoji;
+        }
+        runs.push((&text[start..], current));
+        runs
+    }
+
+    /// Union of bounds of all texts as left, top, right, bottom [px]
+    pub fn bounds(&mut self) -> Option<[f32; 4]> {
+        let elements = std::mem::take(&mut self.elements);
+        let bounds = elements
+            .iter()
+            .flat_map(|state| {
+                let anchor = state
+                    .element
+                    .anchor
+                    .unwrap_or_else(|| state.element.default_anchor());
+                state
+                    .sections
+                    .iter()
+                    .map(move |section| (section, state.element.offset, anchor))
+            })
+            .filter_map(|(section, offset, anchor)| self.section_bounds(section, offset, anchor))
+            .map(|bounds| [bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y])
+            .reduce(|a, b| {
+                [
+                    a[0].min(b[0]),
+                    a[1].min(b[1]),
+                    a[2].max(b[2]),
+                    a[3].max(b[3]),
+                ]
+            });
+        self.elements = elements;
+        bounds
+    }
+
+    fn section_bounds(
+        &mut self,
+        section: &Section,
+        (offset_x, offset_y): (f32, f32),
+        anchor: Anchor,
+    ) -> Option<wgpu_glyph::ab_glyph::Rect> {
+        use wgpu_glyph::GlyphCruncher;
+
+        let (x, y) = section.position;
+        let x = self.target_width as f32 * x + offset_x + self.shift.0;
+        let y = self.target_height as f32 * y + offset_y + self.shift.1;
+        let (h_align, v_align) = anchor.align();
+        self.glyph_blush.glyph_bounds_custom_layout(
+            wgpu_glyph::Section::default()
+                .with_text(
+                    section
+                        .texts
+                        .iter()
+                        .map(|(text, scale)| wgpu_glyph::Text::new(text).with_scale(*scale))
+                        .collect(),
+                )
+                .with_screen_position((x, y)),
+            &self.layout.with_align(h_align, v_align),
+        )
+    }
+
+    /// Text color readable on the picture region under the section
+    fn contrast_color(
+        &mut self,
+        section: &Section,
+        offset: (f32, f32),
+        anchor: Anchor,
+    ) -> [f32; 4] {
+        let (Some(dark_color), Some(_)) = (self.text_dark_color, &self.background) else {
+            return self.text_color();
+        };
+        let Some(bounds) = self.section_bounds(section, offset, anchor) else {
+            return self.text_color();
+        };
+        let (background, picture_size) = self.background.as_ref().unwrap();
+
+        // map the bounds into the background placed as picture pipeline does
+        let (width, height) = background.dimensions();
+        let (scale_x, scale_y) = crate::picture::fit_scale(
+            self.picture_fit,
+            *picture_size,
+            (self.target_width, self.target_height),
+        );
+        let tile = self.picture_fit == crate::picture::PictureFit::Tile;
+        // pixels covered by the bounds, `None` out of the picture unless it is repeated
+        let to_pixels = |min: f32, max: f32, target: u32, scale: f32, size: u32| {
+            let to_pixel = |value: f32| {
+                let texcoord = (1.0 - scale) * 0.5 + scale * value / target as f32;
+                (texcoord * size as f32).floor() as i64
+            };
+            let size = size as i64;
+            let min = to_pixel(min);
+            let max = to_pixel(max).min(min + 2 * size);
+            (min..=max)
+                .map(|pixel| match tile {
+                    true => Some(pixel.rem_euclid(size) as u32),
+                    false => (0..size).contains(&pixel).then_some(pixel as u32),
+                })
+                .collect::<Vec<_>>()
+        };
+        let xs = to_pixels(
+            bounds.min.x,
+            bounds.max.x,
+            self.target_width,
+            scale_x,
+            width,
+        );
+        let ys = to_pixels(
+            bounds.min.y,
+            bounds.max.y,
+            self.target_height,
+            scale_y,
+            height,
+        );
+
+        // letterbox around the picture counts with its own color
+        let [r, g, b, _] = self.letterbox_color;
+        let letterbox = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let sum = ys
+            .iter()
+            .flat_map(|&y| xs.iter().map(move |&x| (x, y)))
+            .map(|(x, y)| match x.zip(y) {
+                Some((x, y)) => background.get_pixel(x, y).0[0] as f32 / 255.0,
+                None => letterbox,
+            })
+            .sum::<f32>();
+        let luminance = sum / (xs.len() * ys.len()).max(1) as f32;
+        if CONTRAST_THRESHOLD < luminance {
+            dark_color
+        } else {
+            self.text_color()
+        }
+    }
+
+    /// Text color, or light color of the palette
+    fn text_color(&self) -> [f32; 4] {
+        match self.palette_color {
+            Some(palette) => {
+                let [r, g, b] = crate::palette::light(palette);
+                [r, g, b, self.text_color[3]]
+            }
+            None => self.text_color,
+        }
+    }
+
+    /// Outline or shadow color, or dark color of the palette
+    fn accent_color(&self, color: [f32; 4]) -> [f32; 4] {
+        match self.palette_color {
+            Some(palette) => {
+                let [r, g, b] = crate::palette::dark(palette);
+                [r, g, b, color[3]]
+            }
+            None => color,
+        }
+    }
+
+    fn format_time(&self, utc: &chrono::DateTime<chrono::Utc>, format: &str) -> String {
+        match self.timezone {
+            Some(timezone) => utc.with_timezone(&timezone).format(format).to_string(),
+            None => utc.with_timezone(&chrono::Local).format(format).to_string(),
+        }
+    }
+}
+
+/// Loads font, along with its data which text shaping reads
+fn load_font(
+    path: &str,
+) -> Result<(Vec<u8>, wgpu_glyph::ab_glyph::FontArc), Box<dyn std::error::Error>> {
+    let data = std::fs::read(path)?;
+    let font = wgpu_glyph::ab_glyph::FontArc::try_from_vec(data.clone())?;
+    Ok((data, font))
+}
+
+/// Converts color written in sRGB into linear one, keeping alpha
+pub fn linear_color([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let linear = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    [linear(r), linear(g), linear(b), a]
+}
+
+/// Splits text into runs with each colon in its own run
+fn split_colons(text: &str) -> Vec<&str> {
+    let mut runs = vec![];
+    for part in text.split_inclusive(':') {
+        match part.strip_suffix(':') {
+            Some(rest) => {
+                if !rest.is_empty() {
+                    runs.push(rest);
+                }
+                runs.push(":");
+            }
+            None => runs.push(part),
+        }
+    }
+    runs
+}
+
+/// First format string of config that strftime cannot parse
+pub fn invalid_format(config: &crate::config::Config) -> Option<&str> {
+    use chrono::format::{Item, StrftimeItems};
+
+    let element_formats = config
+        .elements
+        .iter()
+        .filter_map(|element| element.format.as_deref());
+    config
+        .format
+        .as_deref()
+        .into_iter()
+        .chain([config.date_format.as_str()])
+        .chain(element_formats)
+        .find(|format| StrftimeItems::new(format).any(|item| item == Item::Error))
+}
+
+fn format_remaining(remaining: chrono::Duration) -> String {
+    let seconds = remaining.num_seconds();
+    let (days, hours, minutes, seconds) = (
+        seconds / 86400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    );
+    if 0 < days {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
+
+fn format_elapsed(elapsed: std::time::Duration) -> String {
+    let millis = elapsed.as_millis();
+    let (hours, minutes, seconds, centis) = (
+        millis / 3600000,
+        millis / 60000 % 60,
+        millis / 1000 % 60,
+        millis / 10 % 100,
+    );
+    if 0 < hours {
+        format!("{}:{:02}:{:02}.{:02}", hours, minutes, seconds, centis)
+    } else {
+        format!("{:02}:{:02}.{:02}", minutes, seconds, centis)
+    }
 }
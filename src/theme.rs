@@ -0,0 +1,76 @@
+/// Day of year written as `MM-DD`
+#[derive(Clone, Copy, PartialEq, PartialOrd, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct MonthDay(u32, u32);
+
+impl MonthDay {
+    fn of(date: chrono::NaiveDate) -> Self {
+        use chrono::Datelike;
+
+        Self(date.month(), date.day())
+    }
+}
+
+impl std::str::FromStr for MonthDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (month, day) = s
+            .split_once('-')
+            .and_then(|(month, day)| Some((month.parse().ok()?, day.parse().ok()?)))
+            .filter(|&(month, day)| (1..=12).contains(&month) && (1..=31).contains(&day))
+            .ok_or_else(|| format!("invalid theme date: {:?}", s))?;
+        Ok(Self(month, day))
+    }
+}
+
+impl TryFrom<String> for MonthDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Pictures, text color and greeting used between two days every year
+#[derive(Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Theme {
+    pub from: MonthDay,
+    /// Last day, which may be before `from` to wrap around the new year
+    pub to: MonthDay,
+    pub picture_path: Option<String>,
+    pub text_color: Option<crate::text::Color>,
+    pub greeting: Option<String>,
+}
+
+impl Theme {
+    fn contains(&self, day: MonthDay) -> bool {
+        if self.from <= self.to {
+            self.from <= day && day <= self.to
+        } else {
+            self.from <= day || day <= self.to
+        }
+    }
+}
+
+/// Index of the first theme covering today
+pub fn active(config: &crate::config::Config) -> Option<usize> {
+    let day = MonthDay::of(crate::alarm::now(config.timezone).date());
+    config.themes.iter().position(|theme| theme.contains(day))
+}
+
+/// Config with settings of the theme overridden
+pub fn apply(config: &crate::config::Config, theme: Option<usize>) -> crate::config::Config {
+    let mut config = config.clone();
+    if let Some(theme) = theme.map(|theme| config.themes[theme].clone()) {
+        if let Some(picture_path) = theme.picture_path {
+            config.picture_path = picture_path;
+        }
+        if let Some(text_color) = theme.text_color {
+            config.text_color = text_color;
+        }
+        config.greeting = theme.greeting;
+    }
+    config
+}
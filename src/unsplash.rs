@@ -0,0 +1,113 @@
+const RANDOM_PHOTO_URL: &str = "https://api.unsplash.com/photos/random";
+
+pub struct Unsplash {
+    query: String,
+    access_key: String,
+    width: u32,
+    cache_dir: std::path::PathBuf,
+    cache_size: usize,
+}
+
+#[derive(serde::Deserialize)]
+struct Photo {
+    id: String,
+    urls: PhotoUrls,
+}
+
+#[derive(serde::Deserialize)]
+struct PhotoUrls {
+    raw: String,
+}
+
+impl Unsplash {
+    pub fn new(
+        query: String,
+        access_key: String,
+        width: u32,
+        cache_dir: std::path::PathBuf,
+        cache_size: usize,
+    ) -> Self {
+        if let Err(err) = std::fs::create_dir_all(&cache_dir) {
+            log::warn!("failed to create {}: {}", cache_dir.display(), err);
+        }
+        Self {
+            query,
+            access_key,
+            width,
+            cache_dir,
+            cache_size,
+        }
+    }
+
+    /// Fetches a new random photo, falling back to a cached one when offline
+    pub fn fetch(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        match self.fetch_random() {
+            Ok(data) => Ok(data),
+            Err(err) => {
+                log::error!("failed to fetch unsplash photo: {}", err);
+                self.random_cached()
+            }
+        }
+    }
+
+    fn fetch_random(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        use std::io::Read;
+
+        log::debug!("fetch unsplash photo");
+        let photo: Photo = ureq::get(RANDOM_PHOTO_URL)
+            .set("Accept-Version", "v1")
+            .set("Authorization", &format!("Client-ID {}", self.access_key))
+            .query("query", &self.query)
+            .query("orientation", "landscape")
+            .call()?
+            .into_json()?;
+
+        let mut data = vec![];
+        ureq::get(&photo.urls.raw)
+            .query("w", &self.width.to_string())
+            .query("fm", "jpg")
+            .call()?
+            .into_reader()
+            .read_to_end(&mut data)?;
+
+        let path = self.cache_dir.join(format!("{}.jpg", photo.id));
+        if let Err(err) = std::fs::write(&path, &data) {
+            log::warn!("failed to write {}: {}", path.display(), err);
+        }
+        self.prune();
+        Ok(data)
+    }
+
+    fn cached_paths(&self) -> Vec<(std::path::PathBuf, std::time::SystemTime)> {
+        let Ok(entries) = std::fs::read_dir(&self.cache_dir) else {
+            return vec![];
+        };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|metadata| metadata.modified());
+                modified.ok().map(|modified| (entry.path(), modified))
+            })
+            .collect::<Vec<_>>();
+        paths.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        paths
+    }
+
+    fn prune(&self) {
+        for (path, _) in self.cached_paths().into_iter().skip(self.cache_size) {
+            if let Err(err) = std::fs::remove_file(&path) {
+                log::warn!("failed to remove {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    fn random_cached(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        use rand::seq::SliceRandom;
+
+        let paths = self.cached_paths();
+        let (path, _) = paths
+            .choose(&mut rand::thread_rng())
+            .ok_or("no cached unsplash photo")?;
+        Ok(std::fs::read(path)?)
+    }
+}
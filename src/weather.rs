@@ -0,0 +1,154 @@
+const CURRENT_WEATHER_URL: &str = "https://api.openweathermap.org/data/2.5/weather";
+const ICON_URL: &str = "https://openweathermap.org/img/wn";
+
+#[derive(Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Metric => "metric",
+            Self::Imperial => "imperial",
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Metric => "°C",
+            Self::Imperial => "°F",
+        }
+    }
+}
+
+/// Weather condition pictures are tagged with
+#[derive(Clone, Copy, PartialEq)]
+pub enum Condition {
+    Sun,
+    Clouds,
+    Rain,
+    Snow,
+}
+
+impl Condition {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sun" => Some(Self::Sun),
+            "clouds" => Some(Self::Clouds),
+            "rain" => Some(Self::Rain),
+            "snow" => Some(Self::Snow),
+            _ => None,
+        }
+    }
+
+    /// Maps main condition of OpenWeatherMap, fog and haze are taken as clouds
+    fn from_response(main: &str) -> Self {
+        match main {
+            "Clear" => Self::Sun,
+            "Rain" | "Drizzle" | "Thunderstorm" => Self::Rain,
+            "Snow" => Self::Snow,
+            _ => Self::Clouds,
+        }
+    }
+}
+
+pub struct Weather {
+    pub text: String,
+    pub icon: Option<image::RgbaImage>,
+    pub condition: Option<Condition>,
+}
+
+#[derive(serde::Deserialize)]
+struct Response {
+    weather: Vec<ResponseCondition>,
+    main: ResponseMain,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponseCondition {
+    main: String,
+    icon: String,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponseMain {
+    temp: f32,
+}
+
+pub fn spawn(
+    api_key: String,
+    location: String,
+    units: Units,
+    interval: std::time::Duration,
+) -> std::sync::mpsc::Receiver<Weather> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || loop {
+        log::debug!("fetch weather");
+        match fetch(&api_key, &location, units) {
+            Ok(weather) => {
+                if sender.send(weather).is_err() {
+                    break;
+                }
+            }
+            Err(err) => log::error!("failed to fetch weather: {}", err),
+        }
+        std::thread::sleep(interval);
+    });
+    receiver
+}
+
+fn fetch(
+    api_key: &str,
+    location: &str,
+    units: Units,
+) -> Result<Weather, Box<dyn std::error::Error>> {
+    let response: Response = ureq::get(CURRENT_WEATHER_URL)
+        .query("q", location)
+        .query("appid", api_key)
+        .query("units", units.name())
+        .call()?
+        .into_json()?;
+
+    let condition = response.weather.first();
+    let text = match condition {
+        Some(condition) => format!(
+            "{:.0}{} {}",
+            response.main.temp,
+            units.symbol(),
+            condition.main
+        ),
+        None => format!("{:.0}{}", response.main.temp, units.symbol()),
+    };
+    let icon = match condition {
+        Some(condition) => match fetch_icon(&condition.icon) {
+            Ok(icon) => Some(icon),
+            Err(err) => {
+                log::error!("failed to fetch weather icon: {}", err);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let condition = condition.map(|condition| Condition::from_response(&condition.main));
+
+    Ok(Weather {
+        text,
+        icon,
+        condition,
+    })
+}
+
+fn fetch_icon(icon: &str) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+    use std::io::Read;
+
+    let mut data = vec![];
+    ureq::get(&format!("{}/{}@2x.png", ICON_URL, icon))
+        .call()?
+        .into_reader()
+        .read_to_end(&mut data)?;
+    Ok(image::load_from_memory(&data)?.to_rgba8())
+}
@@ -0,0 +1,211 @@
+/// Draws the clock into a canvas filling the page, with options taken from the query of the page
+/// URL and a picture fetched by the browser, as the other workers of the native build are not
+/// available in browser
+pub fn run() {
+    use wasm_bindgen::JsCast;
+    use winit::platform::web::WindowExtWebSys;
+
+    log::set_logger(&Console).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
+
+    let page = web_sys::window().unwrap();
+    let config = match config(&page) {
+        Ok(config) => config,
+        Err(err) => {
+            log::error!("invalid options in page URL: {}", err);
+            crate::config::Config::default()
+        }
+    };
+    let event_loop = winit::event_loop::EventLoop::new();
+    let window = winit::window::WindowBuilder::new()
+        .with_inner_size(page_size(&page))
+        .build(&event_loop)
+        .unwrap();
+    page.document()
+        .and_then(|document| document.body())
+        .and_then(|body| body.append_child(&window.canvas()).ok())
+        .expect("failed to add canvas to the page");
+
+    // adapter and device are requested asynchronously, so frames wait until they are ready
+    let renderer = std::rc::Rc::new(std::cell::RefCell::new(None::<crate::Renderer>));
+    wasm_bindgen_futures::spawn_local({
+        let renderer = renderer.clone();
+        let config = config.clone();
+        async move {
+            let mut new_renderer =
+                match crate::Renderer::new(Some(crate::Window::Winit(window)), &config).await {
+                    Ok(new_renderer) => new_renderer,
+                    Err(err) => {
+                        log::error!("failed to create renderer: {}", err);
+                        return;
+                    }
+                };
+            new_renderer.set_procedural_background().await;
+            new_renderer.request_redraw();
+            *renderer.borrow_mut() = Some(new_renderer);
+
+            // pages have no picture directory, so only a path given in the URL is fetched
+            if config.picture_path == crate::config::Config::default().picture_path {
+                return;
+            }
+            let size = renderer
+                .borrow()
+                .as_ref()
+                .map(|renderer| (renderer.config.width, renderer.config.height))
+                .unwrap();
+            match fetch_picture(&config.picture_path, size, config.caption).await {
+                Ok(picture) => {
+                    let mut renderer = renderer.borrow_mut();
+                    let renderer = renderer.as_mut().unwrap();
+                    renderer.clear_procedural_background();
+                    renderer.set_picture(picture);
+                    renderer.request_redraw();
+                }
+                Err(err) => {
+                    log::error!("failed to load picture {}: {}", config.picture_path, err)
+                }
+            }
+        }
+    });
+
+    // winit leaves the canvas at its size when the page is resized
+    let on_resize = wasm_bindgen::closure::Closure::<dyn FnMut()>::new({
+        let renderer = renderer.clone();
+        let page = page.clone();
+        move || {
+            let mut renderer = renderer.borrow_mut();
+            let Some(renderer) = renderer.as_mut() else {
+                return;
+            };
+            let Some(crate::Window::Winit(window)) = &renderer.window else {
+                return;
+            };
+            window.set_inner_size(page_size(&page));
+            let new_inner_size = window.inner_size();
+            renderer.resize(new_inner_size);
+            renderer.request_redraw();
+        }
+    });
+    page.add_event_listener_with_callback("resize", on_resize.as_ref().unchecked_ref())
+        .unwrap();
+    on_resize.forget();
+
+    let tick_interval = crate::tick_interval(&config);
+    let animation_interval = std::time::Duration::from_millis(crate::ANIMATION_INTERVAL);
+    use winit::event::Event;
+    use winit::event::StartCause;
+    use winit::event::WindowEvent;
+    event_loop.run(move |event, _, control_flow| {
+        let mut renderer = renderer.borrow_mut();
+        match (event, renderer.as_mut()) {
+            (Event::NewEvents(StartCause::Init), _) => {
+                control_flow.set_wait_timeout(crate::until_next_tick(tick_interval));
+            }
+            (Event::NewEvents(StartCause::ResumeTimeReached { .. }), Some(renderer)) => {
+                // redraws are run by the browser on animation frames
+                renderer.request_redraw();
+                if renderer.is_animating() {
+                    control_flow.set_wait_timeout(animation_interval);
+                } else {
+                    control_flow.set_wait_timeout(crate::until_next_tick(tick_interval));
+                }
+            }
+            (Event::NewEvents(StartCause::ResumeTimeReached { .. }), None) => {
+                control_flow.set_wait_timeout(crate::until_next_tick(tick_interval));
+            }
+            (Event::RedrawRequested(_), Some(renderer)) => renderer.draw(),
+            (
+                Event::WindowEvent {
+                    event: WindowEvent::Resized(new_inner_size),
+                    ..
+                },
+                Some(renderer),
+            ) => renderer.resize(new_inner_size),
+            _ => {}
+        }
+    });
+}
+
+/// Config from the query of the page URL, whose parameters are the command line options without
+/// dashes, e.g. `?face=nixie&picture-path=photo.jpg&fullscreen`
+fn config(page: &web_sys::Window) -> Result<crate::config::Config, String> {
+    use clap::Parser;
+
+    let search = page.location().search().unwrap_or_default();
+    let params = web_sys::UrlSearchParams::new_with_str(&search).unwrap();
+    let mut args = vec!["clock-app".to_string()];
+    for param in js_sys::try_iter(&params).unwrap().unwrap() {
+        let param = js_sys::Array::from(&param.unwrap());
+        let name = param.get(0).as_string().unwrap();
+        let value = param.get(1).as_string().unwrap();
+        match value.is_empty() {
+            true => args.push(format!("--{}", name)),
+            false => args.push(format!("--{}={}", name, value)),
+        }
+    }
+    let args = crate::Args::try_parse_from(args).map_err(|err| err.to_string())?;
+    args.into_config().map_err(|err| err.to_string())
+}
+
+/// Size of the page viewport
+fn page_size(page: &web_sys::Window) -> winit::dpi::LogicalSize<f64> {
+    winit::dpi::LogicalSize::new(
+        page.inner_width().unwrap().as_f64().unwrap(),
+        page.inner_height().unwrap().as_f64().unwrap(),
+    )
+}
+
+/// Picture fetched by the browser, relative to the page or from another origin allowing it
+async fn fetch_picture(
+    url: &str,
+    size: (u32, u32),
+    caption: bool,
+) -> Result<crate::picture::Picture, Box<dyn std::error::Error>> {
+    use wasm_bindgen::JsCast;
+
+    // errors of the browser are JS values, which are reported by their text
+    let js_error = |err: wasm_bindgen::JsValue| format!("{:?}", err);
+    let request = web_sys::Request::new_with_str(url).map_err(js_error)?;
+    let page = web_sys::window().unwrap();
+    let response = wasm_bindgen_futures::JsFuture::from(page.fetch_with_request(&request))
+        .await
+        .map_err(js_error)?
+        .dyn_into::<web_sys::Response>()
+        .map_err(js_error)?;
+    if !response.ok() {
+        return Err(format!("status {}", response.status()).into());
+    }
+    let buffer = wasm_bindgen_futures::JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    let data = js_sys::Uint8Array::new(&buffer).to_vec();
+    crate::picture_store::decode_fetched(url, &data, size, caption)
+}
+
+/// Writes logs into the console of the browser
+struct Console;
+
+impl log::Log for Console {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = wasm_bindgen::JsValue::from(format!(
+            "[{} {}] {}",
+            record.level(),
+            record.target(),
+            record.args()
+        ));
+        match record.level() {
+            log::Level::Error => web_sys::console::error_1(&message),
+            log::Level::Warn => web_sys::console::warn_1(&message),
+            _ => web_sys::console::log_1(&message),
+        }
+    }
+
+    fn flush(&self) {}
+}